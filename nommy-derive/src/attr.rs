@@ -7,12 +7,30 @@ pub struct GlobalAttr {
     pub prefix: Option<syn::Type>,
     pub suffix: Option<syn::Type>,
     pub parse_type: Option<syn::Type>,
+    pub rename_all: Option<syn::LitStr>,
+    pub rename: Option<syn::LitStr>,
+    pub longest_match: bool,
+    pub memoize: bool,
+    pub infix: Option<syn::Type>,
+    pub postfix: Option<syn::Type>,
+    pub precedence: Option<syn::Expr>,
+    pub assoc: Option<syn::LitStr>,
+    /// Every parameter given, used to report duplicates and misplaced parameters
+    pub keys: Vec<proc_macro2::Ident>,
 }
 
-fn parse_type(
+fn parse_type(span: Span, tokens: proc_macro2::token_stream::IntoIter) -> syn::Result<syn::Type> {
+    parse_value(span, tokens)
+}
+
+fn parse_expr(span: Span, tokens: proc_macro2::token_stream::IntoIter) -> syn::Result<syn::Expr> {
+    parse_value(span, tokens)
+}
+
+fn parse_value<T: syn::parse::Parse>(
     span: Span,
     mut tokens: proc_macro2::token_stream::IntoIter,
-) -> syn::Result<syn::Type> {
+) -> syn::Result<T> {
     match tokens.next() {
         Some(TokenTree::Punct(p)) => {
             if p.as_char() != '=' {
@@ -25,10 +43,71 @@ fn parse_type(
 
     let mut stream = TokenStream::new();
     stream.extend(tokens);
+    if stream.is_empty() {
+        return Err(syn::Error::new(span, "expected a value to follow '='"));
+    }
 
     syn::parse2(stream)
 }
 
+fn parse_flag(mut tokens: proc_macro2::token_stream::IntoIter) -> syn::Result<bool> {
+    match tokens.next() {
+        Some(t) => Err(syn::Error::new_spanned(t, "expected no more tokens")),
+        None => Ok(true),
+    }
+}
+
+/// Splits attribute arguments on commas, except for those within generic arguments, such as `Padded<8, 0>`
+fn split_args(mut span: Span, tokens: TokenStream) -> Vec<(Span, TokenStream)> {
+    let mut args = vec![];
+    let mut stream = TokenStream::new();
+    let mut depth = 0_usize;
+    let mut arrow = false;
+    for tt in tokens {
+        match tt {
+            TokenTree::Punct(p) if p.as_char() == ',' && depth == 0 => {
+                args.push((span, std::mem::take(&mut stream)));
+                span = p.span();
+            }
+            TokenTree::Punct(p) => {
+                match p.as_char() {
+                    '<' => depth += 1,
+                    // the `>` of `->` doesn't close anything
+                    '>' if !arrow => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                arrow = p.as_char() == '-' && p.spacing() == proc_macro2::Spacing::Joint;
+                stream.extend(vec![TokenTree::Punct(p)])
+            }
+            _ => {
+                arrow = false;
+                stream.extend(vec![tt])
+            }
+        }
+    }
+    args.push((span, stream));
+    args
+}
+
+/// Errors if `ident` was already given, except for parameters that can be repeated
+fn add_key(keys: &mut Vec<proc_macro2::Ident>, ident: &proc_macro2::Ident) -> syn::Result<()> {
+    if ident != "ignore" && keys.iter().any(|key| key == ident) {
+        return Err(syn::Error::new_spanned(
+            ident,
+            format!("duplicate `{}` parameter", ident),
+        ));
+    }
+    keys.push(ident.clone());
+    Ok(())
+}
+
+pub fn deny(keys: &[proc_macro2::Ident], key: &str, reason: &str) -> syn::Result<()> {
+    match keys.iter().find(|k| *k == key) {
+        Some(k) => Err(syn::Error::new_spanned(k, format!("`{}` {}", key, reason))),
+        None => Ok(()),
+    }
+}
+
 impl GlobalAttr {
     pub fn extend_with(mut self, extend: &Self) -> Self {
         self.ignore.extend_from_slice(&extend.ignore);
@@ -36,6 +115,63 @@ impl GlobalAttr {
         self
     }
 
+    /// Errors on parameters that only apply to enums
+    pub fn check_struct(&self) -> syn::Result<()> {
+        deny(&self.keys, "rename_all", "is only supported on enums")?;
+        deny(&self.keys, "longest_match", "is only supported on enums")?;
+        deny(&self.keys, "rename", "is only supported on unit variants")?;
+        self.deny_operators()
+    }
+
+    /// Errors on parameters that only apply to enum variants
+    pub fn check_enum(&self) -> syn::Result<()> {
+        deny(&self.keys, "rename", "is only supported on unit variants")?;
+        self.deny_operators()
+    }
+
+    fn deny_operators(&self) -> syn::Result<()> {
+        for key in &["infix", "postfix", "precedence", "assoc"] {
+            deny(&self.keys, key, "is only supported on enum variants")?;
+        }
+        Ok(())
+    }
+
+    /// Errors on parameters that only apply to the enum itself, or to other kinds of variant
+    pub fn check_variant(&self, unit: bool) -> syn::Result<()> {
+        deny(
+            &self.keys,
+            "rename_all",
+            "is only supported on the enum, not its variants",
+        )?;
+        deny(
+            &self.keys,
+            "longest_match",
+            "is only supported on the enum, not its variants",
+        )?;
+        deny(
+            &self.keys,
+            "memoize",
+            "is only supported on the enum, not its variants",
+        )?;
+        deny(
+            &self.keys,
+            "parse_type",
+            "is only supported on the enum, not its variants",
+        )?;
+        if !unit {
+            deny(&self.keys, "rename", "is only supported on unit variants")?;
+        } else {
+            for key in &["infix", "postfix", "precedence", "assoc"] {
+                deny(
+                    &self.keys,
+                    key,
+                    "is only supported on variants with operands",
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn parse_attrs(attrs: Vec<syn::Attribute>) -> syn::Result<Self> {
         let mut output = GlobalAttr::default();
         for attr in attrs {
@@ -72,24 +208,11 @@ impl GlobalAttr {
         Ok(())
     }
 
-    pub fn parse_args(&mut self, mut span: Span, tokens: TokenStream) -> syn::Result<()> {
-        let mut stream = TokenStream::new();
-        for tt in tokens {
-            match tt {
-                TokenTree::Punct(p) => {
-                    if p.as_char() == ',' {
-                        let mut tmp = TokenStream::new();
-                        std::mem::swap(&mut stream, &mut tmp);
-                        self.parse_arg(span, tmp)?;
-                        span = p.span();
-                    } else {
-                        stream.extend(vec![TokenTree::Punct(p)])
-                    }
-                }
-                _ => stream.extend(vec![tt]),
-            }
+    pub fn parse_args(&mut self, span: Span, tokens: TokenStream) -> syn::Result<()> {
+        for (span, arg) in split_args(span, tokens) {
+            self.parse_arg(span, arg)?;
         }
-        self.parse_arg(span, stream)
+        Ok(())
     }
 
     pub fn parse_arg(&mut self, span: Span, tokens: TokenStream) -> syn::Result<()> {
@@ -99,13 +222,22 @@ impl GlobalAttr {
             Some(t) => return Err(syn::Error::new_spanned(t, "expected ident")),
             None => return Err(syn::Error::new(span, "expected ident to follow")),
         };
+        add_key(&mut self.keys, &ident)?;
 
         match ident.to_string().as_ref() {
             "ignore" => self.ignore.push(parse_type(ident.span(), tokens)?),
             "prefix" => self.prefix = Some(parse_type(ident.span(), tokens)?),
             "suffix" => self.suffix = Some(parse_type(ident.span(), tokens)?),
             "parse_type" => self.parse_type = Some(parse_type(ident.span(), tokens)?),
-            "debug" => self.debug = true,
+            "rename_all" => self.rename_all = Some(parse_value(ident.span(), tokens)?),
+            "rename" => self.rename = Some(parse_value(ident.span(), tokens)?),
+            "longest_match" => self.longest_match = parse_flag(tokens)?,
+            "memoize" => self.memoize = parse_flag(tokens)?,
+            "infix" => self.infix = Some(parse_type(ident.span(), tokens)?),
+            "postfix" => self.postfix = Some(parse_type(ident.span(), tokens)?),
+            "precedence" => self.precedence = Some(parse_expr(ident.span(), tokens)?),
+            "assoc" => self.assoc = Some(parse_value(ident.span(), tokens)?),
+            "debug" => self.debug = parse_flag(tokens)?,
             _ => return Err(syn::Error::new_spanned(ident, "unknown parameter")),
         }
         Ok(())
@@ -117,6 +249,11 @@ pub struct FieldAttr {
     pub suffix: Option<syn::Type>,
     pub parser: Option<syn::Type>,
     pub vec: VecFieldAttr,
+    pub skip: bool,
+    pub default: Option<syn::Expr>,
+    pub bits: Option<syn::Expr>,
+    /// Every parameter given, used to report duplicates and conflicting parameters
+    pub keys: Vec<proc_macro2::Ident>,
 }
 #[derive(Default)]
 pub struct VecFieldAttr {
@@ -142,9 +279,59 @@ impl FieldAttr {
                 output.parse_attr(attr.tokens)?;
             }
         }
+        output.check()?;
         Ok(output)
     }
 
+    /// Errors on combinations of parameters that cannot be used together
+    fn check(&self) -> syn::Result<()> {
+        let has = |key: &str| self.keys.iter().any(|k| k == key);
+
+        if has("skip") || has("default") {
+            let other = if has("skip") { "skip" } else { "default" };
+            let reason = format!("cannot be used together with `{}`", other);
+            for key in &[
+                "prefix",
+                "suffix",
+                "parser",
+                "inner_parser",
+                "seperated_by",
+                "trailing",
+                "bits",
+            ] {
+                deny(&self.keys, key, &reason)?;
+            }
+            if has("skip") {
+                deny(&self.keys, "default", &reason)?;
+            }
+        }
+
+        if !has("seperated_by") {
+            deny(&self.keys, "trailing", "requires `seperated_by` to be set")?;
+        }
+        if !has("inner_parser") {
+            deny(
+                &self.keys,
+                "seperated_by",
+                "requires `inner_parser` to be set",
+            )?;
+        }
+        if has("inner_parser") {
+            deny(
+                &self.keys,
+                "parser",
+                "cannot be used together with `inner_parser`",
+            )?;
+        }
+        if has("bits") {
+            for key in &["prefix", "suffix", "parser", "inner_parser"] {
+                deny(&self.keys, key, "cannot be used together with `bits`")?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn parse_attr(&mut self, tokens: TokenStream) -> syn::Result<()> {
         for tt in tokens.into_iter() {
             let (inner, span) = match tt {
@@ -171,24 +358,11 @@ impl FieldAttr {
         Ok(())
     }
 
-    pub fn parse_args(&mut self, mut span: Span, tokens: TokenStream) -> syn::Result<()> {
-        let mut stream = TokenStream::new();
-        for tt in tokens {
-            match tt {
-                TokenTree::Punct(p) => {
-                    if p.as_char() == ',' {
-                        let mut tmp = TokenStream::new();
-                        std::mem::swap(&mut stream, &mut tmp);
-                        self.parse_arg(span, tmp)?;
-                        span = p.span();
-                    } else {
-                        stream.extend(vec![TokenTree::Punct(p)])
-                    }
-                }
-                _ => stream.extend(vec![tt]),
-            }
+    pub fn parse_args(&mut self, span: Span, tokens: TokenStream) -> syn::Result<()> {
+        for (span, arg) in split_args(span, tokens) {
+            self.parse_arg(span, arg)?;
         }
-        self.parse_arg(span, stream)
+        Ok(())
     }
 
     pub fn parse_arg(&mut self, span: Span, tokens: TokenStream) -> syn::Result<()> {
@@ -198,6 +372,7 @@ impl FieldAttr {
             Some(t) => return Err(syn::Error::new_spanned(t, "expected ident")),
             None => return Err(syn::Error::new(span, "expected ident to follow")),
         };
+        add_key(&mut self.keys, &ident)?;
 
         match ident.to_string().as_ref() {
             "prefix" => self.prefix = Some(parse_type(ident.span(), tokens)?),
@@ -206,6 +381,9 @@ impl FieldAttr {
             "inner_parser" => self.vec.parser = Some(parse_type(ident.span(), tokens)?),
             "seperated_by" => self.vec.seperated_by = Some(parse_type(ident.span(), tokens)?),
             "trailing" => self.parse_trailing(tokens)?,
+            "skip" => self.skip = parse_flag(tokens)?,
+            "default" => self.default = Some(parse_expr(ident.span(), tokens)?),
+            "bits" => self.bits = Some(parse_expr(ident.span(), tokens)?),
             _ => return Err(syn::Error::new_spanned(ident, "unknown parameter")),
         }
         Ok(())
@@ -0,0 +1,64 @@
+/// Case conventions supported by `#[nommy(rename_all = "...")]`
+#[derive(Copy, Clone)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    pub fn from_lit(lit: &syn::LitStr) -> syn::Result<Self> {
+        match lit.value().as_str() {
+            "lowercase" => Ok(RenameRule::Lower),
+            "UPPERCASE" => Ok(RenameRule::Upper),
+            "PascalCase" => Ok(RenameRule::Pascal),
+            "camelCase" => Ok(RenameRule::Camel),
+            "snake_case" => Ok(RenameRule::Snake),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnake),
+            "kebab-case" => Ok(RenameRule::Kebab),
+            "SCREAMING-KEBAB-CASE" => Ok(RenameRule::ScreamingKebab),
+            _ => Err(syn::Error::new(
+                lit.span(),
+                "expected one of \"lowercase\", \"UPPERCASE\", \"PascalCase\", \"camelCase\", \
+                \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\" or \"SCREAMING-KEBAB-CASE\"",
+            )),
+        }
+    }
+
+    /// Applies the case convention to a `PascalCase` variant name
+    pub fn apply(self, variant: &str) -> String {
+        match self {
+            RenameRule::Lower => variant.to_lowercase(),
+            RenameRule::Upper => variant.to_uppercase(),
+            RenameRule::Pascal => variant.to_owned(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+                match chars.next() {
+                    Some(first) => first.to_lowercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+            RenameRule::Snake => split_words(variant, '_').to_lowercase(),
+            RenameRule::ScreamingSnake => split_words(variant, '_').to_uppercase(),
+            RenameRule::Kebab => split_words(variant, '-').to_lowercase(),
+            RenameRule::ScreamingKebab => split_words(variant, '-').to_uppercase(),
+        }
+    }
+}
+
+/// Inserts `sep` before every uppercase character except the first
+fn split_words(variant: &str, sep: char) -> String {
+    let mut output = String::new();
+    for (i, c) in variant.char_indices() {
+        if i > 0 && c.is_uppercase() {
+            output.push(sep);
+        }
+        output.push(c);
+    }
+    output
+}
@@ -0,0 +1,96 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{attr::VecFieldAttr, walker::Backend};
+
+/// Builds the body of `Describe::describe`, listing the rules in the same order that [`Builder`](crate::fn_impl::Builder) parses them.
+/// Any number of the ignore types can be wherever the parser would skip over ignored values
+pub struct Describer;
+
+impl Backend for Describer {
+    fn trait_path() -> TokenStream {
+        quote! { ::nommy::Describe }
+    }
+
+    fn fix(generic: &syn::Type, ty: &syn::Type) -> TokenStream {
+        quote! { <#ty as ::nommy::Describe<#generic>>::describe(grammar) }
+    }
+
+    fn bits(_key: &proc_macro2::Ident, bits: &syn::Expr) -> syn::Result<TokenStream> {
+        Ok(quote! {
+            ::nommy::grammar::Rule::Special(format!("{} bits", #bits))
+        })
+    }
+
+    fn vec(
+        _name: &syn::Ident,
+        elem: TokenStream,
+        between: TokenStream,
+        attrs: &VecFieldAttr,
+    ) -> TokenStream {
+        let (min, max) = match &attrs.count {
+            Some(count) => (quote! { #count }, quote! { Some(#count) }),
+            None => (
+                attrs
+                    .min
+                    .as_ref()
+                    .map_or(quote! { 0 }, |min| quote! { #min }),
+                attrs
+                    .max
+                    .as_ref()
+                    .map_or(quote! { None }, |max| quote! { Some(#max) }),
+            ),
+        };
+
+        let repeat = |elem: TokenStream, between: TokenStream| {
+            quote! {
+                ::nommy::grammar::Rule::Repeat {
+                    rule: Box::new(#elem),
+                    min: #min,
+                    max: #max,
+                    separator: Some(Box::new(#between)).filter(|sep| !sep.is_empty()),
+                }
+            }
+        };
+        match (&attrs.seperated_by, attrs.trailing) {
+            (Some(_), Some(true)) => repeat(
+                quote! { ::nommy::grammar::Rule::sequence(vec![#elem, #between]) },
+                quote! { ::nommy::grammar::Rule::empty() },
+            ),
+            (Some(_), Some(false)) => {
+                let repeat = repeat(elem, between.clone());
+                quote! {
+                    ::nommy::grammar::Rule::sequence(vec![#repeat, ::nommy::grammar::Rule::optional(#between)])
+                }
+            }
+            _ => repeat(elem, between),
+        }
+    }
+
+    fn ignored(ignore: &[syn::Type]) -> &[syn::Type] {
+        ignore
+    }
+
+    fn ignore(generic: &syn::Type, ignored: &[syn::Type]) -> TokenStream {
+        let rules = ignored.iter().map(|ty| Self::fix(generic, ty));
+        quote! {
+            ::nommy::grammar::Rule::repeat(::nommy::grammar::Rule::choice(vec![#(#rules),*]), 0)
+        }
+    }
+
+    fn join(parts: Vec<TokenStream>) -> TokenStream {
+        quote! { ::nommy::grammar::Rule::sequence(vec![#(#parts),*]) }
+    }
+
+    fn method(
+        _generic: &syn::Type,
+        _pattern: Option<TokenStream>,
+        body: TokenStream,
+    ) -> TokenStream {
+        quote! {
+            fn describe(grammar: &mut ::nommy::grammar::Grammar) -> ::nommy::grammar::Rule {
+                grammar.rule_of::<Self>(|grammar| #body)
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use super::{Enum, EnumVariant};
+use crate::{attr::GlobalAttr, parsers::FieldType};
+
+/// Whether `ty` is the enum itself, either directly or in a `Box`
+fn is_enum(ty: &syn::Type, name: &syn::Ident) -> bool {
+    let path = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => &path.path,
+        syn::Type::Paren(ty) => return is_enum(&ty.elem, name),
+        syn::Type::Group(ty) => return is_enum(&ty.elem, name),
+        _ => return false,
+    };
+    let segment = match path.segments.last() {
+        Some(segment) => segment,
+        None => return false,
+    };
+    if path.segments.len() == 1 && (segment.ident == *name || segment.ident == "Self") {
+        return true;
+    }
+    if segment.ident != "Box" {
+        return false;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(ty) => is_enum(ty, name),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The type the variant parses first, if nothing comes before it
+fn leading_type<'a, F: FieldType>(attrs: &GlobalAttr, fields: &'a [F]) -> Option<&'a syn::Type> {
+    if attrs.prefix.is_some() {
+        return None;
+    }
+    let field = fields
+        .iter()
+        .find(|f| !f.attrs().skip && f.attrs().default.is_none())?;
+    let field_attrs = field.attrs();
+    if field_attrs.prefix.is_some() || field_attrs.vec.is_some() || field_attrs.bits.is_some() {
+        return None;
+    }
+    Some(field_attrs.parser.as_ref().unwrap_or_else(|| field.ty()))
+}
+
+/// Whether the variant starts by parsing the enum again, returning the type it does so with
+pub fn left_recursive<'a>(enum_: &Enum, variant: &'a EnumVariant) -> Option<&'a syn::Type> {
+    if enum_.attrs.prefix.is_some() {
+        return None;
+    }
+    let leading = match variant {
+        EnumVariant::Named(v) => leading_type(&v.attrs, &v.fields),
+        EnumVariant::Unnamed(v) => leading_type(&v.attrs, &v.fields),
+        EnumVariant::Unit(_) => None,
+    };
+    leading.filter(|ty| is_enum(ty, &enum_.name))
+}
+
+/// Errors if a variant starts by parsing the enum again, since without memoization
+/// the derived parser would recurse forever
+pub fn check(enum_: &Enum) -> syn::Result<()> {
+    if enum_.attrs.memoize {
+        return Ok(());
+    }
+    for variant in enum_.variants.iter().filter(|v| v.operator().is_none()) {
+        if let Some(ty) = left_recursive(enum_, variant) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!(
+                    "variant `{}` of `{}` is left recursive, add `#[nommy(memoize)]` to `{}` to parse it",
+                    variant.name(),
+                    enum_.name,
+                    enum_.name,
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
@@ -1,10 +1,14 @@
+mod left_recursion;
 mod named;
+mod pratt;
 mod unit;
 mod unnamed;
+mod unreachable;
 
 use std::convert::TryInto;
 
 use named::EnumVariantNamed;
+use pratt::Operator;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, ToTokens};
 use unit::EnumVariantUnit;
@@ -12,13 +16,20 @@ use unnamed::EnumVariantUnnamed;
 
 use crate::{
     attr::GlobalAttr,
-    fn_impl::{parse_or, BuildOutput, Builder},
+    case::RenameRule,
+    describe_impl::Describer,
+    fn_impl::{impl_line, in_context, memoize, parse_or, BuildOutput, Builder},
+    generate_impl::{is_recursive, Generator},
+    parsers::{check_bits, FieldType, NamedField, UnnamedField},
+    print_impl::Printer,
+    walker::{trait_impl, Backend, Walker},
+    Item,
 };
 
 pub struct Enum {
     pub attrs: GlobalAttr,
     pub name: syn::Ident,
-    pub args: Vec<syn::Ident>,
+    pub generics: syn::Generics,
     pub variants: Vec<EnumVariant>,
     generic: syn::Type,
 }
@@ -38,18 +49,24 @@ impl ToTokens for Enum {
         let Enum {
             attrs,
             name,
-            args,
+            generics,
             variants: vars,
             generic,
         } = self;
 
+        if !attrs.longest_match {
+            tokens.extend(unreachable::unreachable_warnings(self));
+        }
+
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
         let mut outer_builder = Builder::new(generic, name, &attrs.parse_type);
 
         outer_builder.create_ignore(&attrs.ignore);
         outer_builder.add_fix(&attrs.prefix, "prefix", format!("enum `{}`", name));
-        outer_builder.start_variants();
+        outer_builder.start_variants(attrs.longest_match);
 
-        for v in vars {
+        // operators are parsed by `::nommy::pratt`, around the other variants
+        for v in vars.iter().filter(|v| v.operator().is_none()) {
             let BuildOutput {
                 peek_impl,
                 parse_impl,
@@ -63,6 +80,19 @@ impl ToTokens for Enum {
             let peek_name = format_ident!("__peek_{}", var_name);
             let parse_result = map_vars!(v => |n| n.result(&self));
 
+            // parsing the enum again takes the seed being grown by `::nommy::memo::parse`,
+            // so only do so if the rest of the variant matches too
+            let gate = if left_recursion::left_recursive(self, v).is_some() {
+                let error = format!("variant `{}` of `{}` did not match", v.name(), name);
+                quote! {
+                    if !Self::#peek_name(&mut input.cursor()) {
+                        return Err(::nommy::eyre::eyre!(#error));
+                    }
+                }
+            } else {
+                TokenStream::new()
+            };
+
             outer_builder.add_variant(&peek_name, &parse_name);
 
 
@@ -88,11 +118,12 @@ impl ToTokens for Enum {
 
             tokens.extend(quote!{
                 #[automatically_derived]
-                impl<#(#args),*> #name<#(#args),*>
+                impl #impl_generics #name #ty_generics #where_clause
                 {
                     #parse_fn {
                         use ::nommy::eyre::WrapErr;
                         use ::std::convert::TryInto;
+                        #gate
                         #parse_impl
                         #parse_result
                     }
@@ -110,42 +141,191 @@ impl ToTokens for Enum {
 
         outer_builder.add_fix(&attrs.suffix, "suffix", format!("enum `{}`", name));
 
-        let BuildOutput {
-            peek_impl,
-            parse_impl,
-            wc,
-        } = outer_builder.build();
-
-        let impl_line = match attrs.parse_type {
-            Some(_) => quote!{
-                impl<#(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*>
-            },
-            None => quote!{
-                impl<#generic, #(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*> where #wc
-            },
+        let (parse_impl, peek_impl, mut wc) = if vars.iter().any(|v| v.operator().is_some()) {
+            let (pratt_impl, wc) = pratt::pratt_impl(self, outer_builder.build());
+            tokens.extend(pratt_impl);
+            (
+                quote! { ::nommy::pratt::parse::<Self, #generic>(input) },
+                quote! { ::nommy::pratt::peek::<Self, #generic>(input) },
+                wc,
+            )
+        } else {
+            let BuildOutput {
+                peek_impl,
+                parse_impl,
+                wc,
+            } = outer_builder.build();
+            (
+                quote! {
+                    use ::nommy::eyre::WrapErr;
+                    use ::std::convert::TryInto;
+                    #parse_impl
+
+                    Ok(result)
+                },
+                quote! {
+                    #peek_impl
+
+                    true
+                },
+                wc,
+            )
         };
 
+        let (parse_impl, peek_impl) = in_context(parse_impl, peek_impl);
+        let (parse_body, peek_body) = memoize(attrs, &mut wc, parse_impl, peek_impl);
+
+        let impl_line = impl_line(name, generics, &attrs.parse_type, generic, &wc);
+
         tokens.extend(quote!{
             #[automatically_derived]
             #impl_line {
                 fn parse(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> {
-                    use ::nommy::eyre::WrapErr;
-                    use ::std::convert::TryInto;
-                    #parse_impl
-
-                    Ok(result)
+                    #parse_body
                 }
 
                 fn peek(input: &mut impl ::nommy::Buffer<#generic>) -> bool {
-                    #peek_impl
-
-                    true
+                    #peek_body
                 }
             }
         })
     }
 }
 
+impl Item for Enum {
+    fn parse_tokens(&self) -> TokenStream {
+        self.to_token_stream()
+    }
+
+    fn print_tokens(&self) -> syn::Result<TokenStream> {
+        let Enum {
+            attrs,
+            name,
+            generics,
+            variants,
+            generic,
+        } = self;
+
+        let mut printer = Walker::<Printer>::new(generic, name);
+        printer.create_ignore(&attrs.ignore);
+        printer.add_fix(&attrs.prefix);
+
+        let mut arms = TokenStream::new();
+        for v in variants {
+            let (print_impl, wc) = map_vars!(v => |n| {
+                let fn_impl = n.fn_impl(self);
+                Walker::<Printer>::fields(generic, name, fn_impl.attrs, fn_impl.fields)?
+            });
+            printer.add_where_raw(wc);
+
+            let var_name = v.name();
+            let pattern = match v {
+                EnumVariant::Named(v) => {
+                    let names = v.fields.iter().map(|f| &f.name);
+                    quote! { Self::#var_name { #(#names,)* } }
+                }
+                EnumVariant::Unnamed(v) => {
+                    let names = v.fields.iter().enumerate().map(|(i, f)| f.name(i));
+                    quote! { Self::#var_name(#(#names,)*) }
+                }
+                EnumVariant::Unit(_) => quote! { Self::#var_name },
+            };
+            arms.extend(quote! {
+                #pattern => {
+                    #print_impl
+                }
+            });
+        }
+        printer.add_raw(quote! {
+            match self {
+                #arms
+            }
+        });
+
+        printer.add_fix(&attrs.suffix);
+
+        let (print_impl, wc) = printer.build();
+        let method = Printer::method(generic, None, print_impl);
+        Ok(trait_impl::<Printer>(name, generics, attrs, generic, &wc, method))
+    }
+
+    fn generate_tokens(&self) -> syn::Result<TokenStream> {
+        let Enum {
+            attrs,
+            name,
+            generics,
+            variants,
+            generic,
+        } = self;
+
+        let mut generator = Walker::<Generator>::new(generic, name);
+        generator.create_ignore(&attrs.ignore);
+        generator.add_fix(&attrs.prefix);
+
+        let mut arms = TokenStream::new();
+        let mut recursive = Vec::new();
+        for (i, v) in variants.iter().enumerate() {
+            let (generate_impl, wc) = map_vars!(v => |n| {
+                let fn_impl = n.fn_impl(self);
+                recursive.push(is_recursive(fn_impl.fields, name));
+                Walker::<Generator>::fields(generic, name, fn_impl.attrs, fn_impl.fields)?
+            });
+            generator.add_where_raw(wc);
+
+            arms.extend(quote! {
+                #i => {
+                    #generate_impl
+                }
+            });
+        }
+        generator.add_raw(quote! {
+            match gen.choose_variant(&[#(#recursive),*]) {
+                #arms
+                _ => unreachable!(),
+            }
+        });
+
+        generator.add_fix(&attrs.suffix);
+
+        let (generate_impl, wc) = generator.build();
+        let method = Generator::method(generic, None, generate_impl);
+        Ok(trait_impl::<Generator>(name, generics, attrs, generic, &wc, method))
+    }
+
+    fn describe_tokens(&self) -> syn::Result<TokenStream> {
+        let Enum {
+            attrs,
+            name,
+            generics,
+            variants,
+            generic,
+        } = self;
+
+        let mut describer = Walker::<Describer>::new(generic, name);
+        describer.create_ignore(&attrs.ignore);
+        describer.add_fix(&attrs.prefix);
+
+        let mut choices = Vec::new();
+        for v in variants {
+            let (describe_impl, wc) = map_vars!(v => |n| {
+                let fn_impl = n.fn_impl(self);
+                Walker::<Describer>::fields(generic, name, fn_impl.attrs, fn_impl.fields)?
+            });
+            describer.add_where_raw(wc);
+            choices.push(describe_impl);
+        }
+        describer.add_raw(quote! {
+            ::nommy::grammar::Rule::choice(vec![#(#choices),*])
+        });
+
+        describer.add_fix(&attrs.suffix);
+
+        let (describe_impl, wc) = describer.build();
+        let method = Describer::method(generic, None, describe_impl);
+        Ok(trait_impl::<Describer>(name, generics, attrs, generic, &wc, method))
+    }
+}
+
 impl Enum {
     pub fn new(
         name: syn::Ident,
@@ -153,51 +333,117 @@ impl Enum {
         attrs: Vec<syn::Attribute>,
         enum_data: syn::DataEnum,
     ) -> syn::Result<Self> {
-        let args = generics.type_params().cloned().map(|tp| tp.ident).collect();
         let attrs = GlobalAttr::parse_attrs(attrs)?;
+        attrs.check_enum()?;
         let generic = parse_or(&attrs.parse_type);
+        let rename_all = match &attrs.rename_all {
+            Some(lit) => Some(RenameRule::from_lit(lit)?),
+            None => None,
+        };
 
         let variants = enum_data
             .variants
             .into_iter()
-            .map(|v| match v.fields {
-                syn::Fields::Named(named) => Ok(EnumVariant::Named(EnumVariantNamed {
-                    name: v.ident,
-                    attrs: GlobalAttr::parse_attrs(v.attrs)?.extend_with(&attrs),
-                    fields: named
-                        .named
-                        .into_iter()
-                        .map(|f| f.try_into())
-                        .collect::<syn::Result<_>>()?,
-                })),
-                syn::Fields::Unnamed(unnamed) => Ok(EnumVariant::Unnamed(EnumVariantUnnamed {
-                    name: v.ident,
-                    attrs: GlobalAttr::parse_attrs(v.attrs)?.extend_with(&attrs),
-                    fields: unnamed
-                        .unnamed
-                        .into_iter()
-                        .map(|f| f.try_into())
-                        .collect::<syn::Result<_>>()?,
-                })),
-                syn::Fields::Unit => Ok(EnumVariant::Unit(EnumVariantUnit {
-                    name: v.ident,
-                    attrs: GlobalAttr::parse_attrs(v.attrs)?.extend_with(&attrs),
-                })),
+            .map(|v| {
+                let var_attrs = GlobalAttr::parse_attrs(v.attrs)?;
+                var_attrs.check_variant(matches!(v.fields, syn::Fields::Unit))?;
+                let mut var_attrs = var_attrs.extend_with(&attrs);
+                match v.fields {
+                    syn::Fields::Named(named) => {
+                        let mut fields: Vec<NamedField> = named
+                            .named
+                            .into_iter()
+                            .map(|f| f.try_into())
+                            .collect::<syn::Result<_>>()?;
+                        check_bits(&var_attrs.parse_type, &fields)?;
+                        let operator = Operator::new(&v.ident, &mut var_attrs, &mut fields)?;
+                        Ok(EnumVariant::Named(EnumVariantNamed {
+                            name: v.ident,
+                            attrs: var_attrs,
+                            fields,
+                            operator,
+                        }))
+                    }
+                    syn::Fields::Unnamed(unnamed) => {
+                        let mut fields: Vec<UnnamedField> = unnamed
+                            .unnamed
+                            .into_iter()
+                            .map(|f| f.try_into())
+                            .collect::<syn::Result<_>>()?;
+                        check_bits(&var_attrs.parse_type, &fields)?;
+                        let operator = Operator::new(&v.ident, &mut var_attrs, &mut fields)?;
+                        Ok(EnumVariant::Unnamed(EnumVariantUnnamed {
+                            name: v.ident,
+                            attrs: var_attrs,
+                            fields,
+                            operator,
+                        }))
+                    }
+                    syn::Fields::Unit => Ok(EnumVariant::Unit(EnumVariantUnit::new(
+                        v.ident, var_attrs, rename_all,
+                    )?)),
+                }
             })
             .collect::<syn::Result<_>>()?;
+        let variants = longest_tags_first(variants);
 
-        Ok(Enum {
+        let enum_ = Enum {
             name,
             attrs,
-            args,
+            generics,
             variants,
             generic,
-        })
+        };
+        pratt::check(&enum_)?;
+        left_recursion::check(&enum_)?;
+        Ok(enum_)
     }
 }
 
+/// Reorders the tagged unit variants so that longer tags are tried first,
+/// otherwise a tag such as `"PUT"` would be unreachable behind `"P"`.
+/// Only the positions already held by tagged variants are permuted,
+/// so the order relative to any other variants is kept.
+fn longest_tags_first(variants: Vec<EnumVariant>) -> Vec<EnumVariant> {
+    let tag_len = |v: &EnumVariant| match v {
+        EnumVariant::Unit(u) => u.tag.as_ref().map(|tag| tag.chars().count()),
+        _ => None,
+    };
+
+    let positions: Vec<usize> = (0..variants.len())
+        .filter(|&i| tag_len(&variants[i]).is_some())
+        .collect();
+    let mut sorted = positions.clone();
+    sorted.sort_by_key(|&i| std::cmp::Reverse(tag_len(&variants[i])));
+
+    let mut order: Vec<usize> = (0..variants.len()).collect();
+    for (&pos, &i) in positions.iter().zip(&sorted) {
+        order[pos] = i;
+    }
+
+    let mut variants: Vec<Option<EnumVariant>> = variants.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .map(|i| variants[i].take().unwrap())
+        .collect()
+}
+
 pub enum EnumVariant {
     Named(EnumVariantNamed),
     Unnamed(EnumVariantUnnamed),
     Unit(EnumVariantUnit),
 }
+
+impl EnumVariant {
+    pub fn name(&self) -> &syn::Ident {
+        map_vars!(self => |v| &v.name)
+    }
+
+    pub fn operator(&self) -> Option<&Operator> {
+        match self {
+            EnumVariant::Named(v) => v.operator.as_ref(),
+            EnumVariant::Unnamed(v) => v.operator.as_ref(),
+            EnumVariant::Unit(_) => None,
+        }
+    }
+}
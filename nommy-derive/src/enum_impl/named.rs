@@ -1,4 +1,4 @@
-use super::Enum;
+use super::{pratt::Operator, Enum};
 use crate::{attr::GlobalAttr, fn_impl::FnImpl, parsers::NamedField};
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -7,6 +7,7 @@ pub struct EnumVariantNamed {
     pub name: syn::Ident,
     pub attrs: GlobalAttr,
     pub fields: Vec<NamedField>,
+    pub operator: Option<Operator>,
 }
 
 impl EnumVariantNamed {
@@ -15,6 +16,7 @@ impl EnumVariantNamed {
             name,
             attrs,
             fields,
+            ..
         } = self;
 
         FnImpl {
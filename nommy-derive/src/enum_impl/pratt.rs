@@ -0,0 +1,277 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use super::{unreachable::tag_literal, Enum, EnumVariant};
+use crate::{
+    attr::{deny, GlobalAttr},
+    fn_impl::{trait_impl_line, BuildOutput, Builder},
+    parsers::FieldType,
+};
+
+pub enum Fixity {
+    Prefix,
+    Infix(TokenStream),
+    Postfix,
+}
+
+/// An operator variant of an enum, parsed with `::nommy::pratt`
+pub struct Operator {
+    pub fixity: Fixity,
+    pub op: syn::Type,
+    pub precedence: syn::Expr,
+}
+
+impl Operator {
+    /// Reads the operator that a variant defines with `precedence`, if any.
+    /// The operator's tag is moved onto the operand fields, so printing and describing the variant
+    /// treat it as a plain sequence
+    pub fn new<F: FieldType>(
+        name: &syn::Ident,
+        attrs: &mut GlobalAttr,
+        fields: &mut [F],
+    ) -> syn::Result<Option<Self>> {
+        let precedence = match &attrs.precedence {
+            Some(precedence) => precedence.clone(),
+            None => {
+                for key in &["infix", "postfix", "assoc"] {
+                    deny(&attrs.keys, key, "needs a `precedence`")?;
+                }
+                return Ok(None);
+            }
+        };
+
+        let (fixity, op, operands) = match (&attrs.prefix, &attrs.infix, &attrs.postfix) {
+            (Some(op), None, None) => (Fixity::Prefix, op.clone(), 1),
+            (None, Some(op), None) => {
+                let assoc = match &attrs.assoc {
+                    None => quote! { ::nommy::pratt::Assoc::Left },
+                    Some(assoc) if assoc.value() == "left" => {
+                        quote! { ::nommy::pratt::Assoc::Left }
+                    }
+                    Some(assoc) if assoc.value() == "right" => {
+                        quote! { ::nommy::pratt::Assoc::Right }
+                    }
+                    Some(assoc) => {
+                        return Err(syn::Error::new_spanned(
+                            assoc,
+                            "expected `assoc` to be \"left\" or \"right\"",
+                        ))
+                    }
+                };
+                (Fixity::Infix(assoc), op.clone(), 2)
+            }
+            (None, None, Some(op)) => (Fixity::Postfix, op.clone(), 1),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &precedence,
+                    "`precedence` needs exactly one of `prefix`, `infix` or `postfix`",
+                ))
+            }
+        };
+        if !matches!(fixity, Fixity::Infix(_)) {
+            deny(
+                &attrs.keys,
+                "assoc",
+                "is only supported on `infix` operators",
+            )?;
+        }
+        deny(
+            &attrs.keys,
+            "suffix",
+            "is not supported on operator variants",
+        )?;
+
+        if fields.len() != operands {
+            return Err(syn::Error::new_spanned(
+                name,
+                format!(
+                    "operator variant `{}` needs exactly {} field{}, for its operand{}",
+                    name,
+                    operands,
+                    if operands == 1 { "" } else { "s" },
+                    if operands == 1 { "" } else { "s" },
+                ),
+            ));
+        }
+        for field in fields.iter() {
+            if let Some(key) = field.attrs().keys.first() {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "the operands of operator variants can't have attributes",
+                ));
+            }
+        }
+
+        match fixity {
+            Fixity::Prefix => {}
+            Fixity::Infix(_) => fields[1].attrs_mut().prefix = Some(op.clone()),
+            Fixity::Postfix => fields[0].attrs_mut().suffix = Some(op.clone()),
+        }
+
+        Ok(Some(Self {
+            fixity,
+            op,
+            precedence,
+        }))
+    }
+}
+
+/// Errors on the enum parameters that can't be used together with operators
+pub fn check(enum_: &Enum) -> syn::Result<()> {
+    if enum_.variants.iter().any(|v| v.operator().is_some()) {
+        deny(
+            &enum_.attrs.keys,
+            "prefix",
+            "is not supported on enums with operators",
+        )?;
+        deny(
+            &enum_.attrs.keys,
+            "suffix",
+            "is not supported on enums with operators",
+        )?;
+    }
+    Ok(())
+}
+
+/// Builds the `Pratt` impl for an enum with operators, where `atom` parses any of the other variants.
+/// Returns the impl and the where clause needed for it
+pub fn pratt_impl(enum_: &Enum, atom: BuildOutput) -> (TokenStream, TokenStream) {
+    let Enum {
+        attrs,
+        name,
+        generics,
+        generic,
+        ..
+    } = enum_;
+
+    let mut operators: Vec<(usize, &EnumVariant, &Operator)> = enum_
+        .variants
+        .iter()
+        .enumerate()
+        .filter_map(|(i, v)| Some((i, v, v.operator()?)))
+        .collect();
+    // so that `**` is tried before `*`
+    operators.sort_by_key(|(_, _, operator)| {
+        std::cmp::Reverse(tag_literal(&operator.op).map_or(0, |tag| tag.len()))
+    });
+
+    let mut wc = atom.wc;
+    let mut readers = TokenStream::new();
+    for (fixity, method, output) in &[
+        ("prefix", quote! { prefix }, quote! { (usize, u32) }),
+        (
+            "infix",
+            quote! { infix },
+            quote! { (usize, u32, ::nommy::pratt::Assoc) },
+        ),
+        ("postfix", quote! { postfix }, quote! { (usize, u32) }),
+    ] {
+        let mut builder = Builder::new(generic, name, &attrs.parse_type);
+        builder.create_ignore(&attrs.ignore);
+        let mut any = false;
+        for (i, _, operator) in &operators {
+            let precedence = &operator.precedence;
+            let result = match (&operator.fixity, *fixity) {
+                (Fixity::Prefix, "prefix") | (Fixity::Postfix, "postfix") => {
+                    quote! { (#i, #precedence) }
+                }
+                (Fixity::Infix(assoc), "infix") => quote! { (#i, #precedence, #assoc) },
+                _ => continue,
+            };
+            builder.add_operator(&operator.op, result);
+            any = true;
+        }
+        if !any {
+            continue;
+        }
+        let BuildOutput {
+            parse_impl,
+            wc: op_wc,
+            ..
+        } = builder.build();
+        wc.extend(op_wc);
+        readers.extend(quote! {
+            fn #method(input: &mut impl ::nommy::Buffer<#generic>) -> ::std::option::Option<#output> {
+                #parse_impl
+                None
+            }
+        });
+    }
+
+    let mut unary = TokenStream::new();
+    let mut binary = TokenStream::new();
+    for (i, variant, operator) in &operators {
+        let variant_name = variant.name();
+        let build = |operands: &[TokenStream]| match variant {
+            EnumVariant::Named(v) => {
+                let names = v.fields.iter().map(|f| &f.name);
+                quote! { Self::#variant_name { #(#names: ::std::convert::From::from(#operands),)* } }
+            }
+            _ => quote! { Self::#variant_name(#(::std::convert::From::from(#operands),)*) },
+        };
+        match operator.fixity {
+            Fixity::Infix(_) => {
+                let node = build(&[quote! { lhs }, quote! { rhs }]);
+                binary.extend(quote! { #i => #node, });
+            }
+            _ => {
+                let node = build(&[quote! { operand }]);
+                unary.extend(quote! { #i => #node, });
+            }
+        }
+    }
+
+    let BuildOutput {
+        peek_impl,
+        parse_impl,
+        ..
+    } = atom;
+    let impl_line = trait_impl_line(
+        quote! { ::nommy::pratt::Pratt },
+        name,
+        generics,
+        &attrs.parse_type,
+        generic,
+        &wc,
+    );
+
+    let tokens = quote! {
+        #[automatically_derived]
+        #impl_line {
+            type Op = usize;
+
+            fn atom(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> {
+                use ::nommy::eyre::WrapErr;
+                use ::std::convert::TryInto;
+                #parse_impl
+
+                Ok(result)
+            }
+
+            fn peek_atom(input: &mut impl ::nommy::Buffer<#generic>) -> bool {
+                #peek_impl
+
+                true
+            }
+
+            #readers
+
+            #[allow(unused_variables)]
+            fn unary(op: usize, operand: Self) -> Self {
+                match op {
+                    #unary
+                    _ => unreachable!(),
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn binary(op: usize, lhs: Self, rhs: Self) -> Self {
+                match op {
+                    #binary
+                    _ => unreachable!(),
+                }
+            }
+        }
+    };
+    (tokens, wc)
+}
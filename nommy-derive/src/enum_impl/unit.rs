@@ -1,16 +1,44 @@
 use super::Enum;
-use crate::{attr::GlobalAttr, fn_impl::FnImpl, parsers::NamedField};
+use crate::{attr::GlobalAttr, case::RenameRule, fn_impl::FnImpl, parsers::NamedField};
 use proc_macro2::TokenStream;
 use quote::quote;
 
 pub struct EnumVariantUnit {
     pub name: syn::Ident,
     pub attrs: GlobalAttr,
+    /// Tag derived from `rename` or `rename_all`, parsed as the variant's prefix
+    pub tag: Option<String>,
 }
 
 impl EnumVariantUnit {
+    pub fn new(
+        name: syn::Ident,
+        mut attrs: GlobalAttr,
+        rename_all: Option<RenameRule>,
+    ) -> syn::Result<Self> {
+        let tag = match (&attrs.rename, rename_all) {
+            (Some(rename), _) => {
+                if attrs.prefix.is_some() {
+                    return Err(syn::Error::new(
+                        rename.span(),
+                        "`rename` cannot be used together with `prefix`",
+                    ));
+                }
+                Some(rename.value())
+            }
+            (None, Some(rule)) if attrs.prefix.is_none() => Some(rule.apply(&name.to_string())),
+            _ => None,
+        };
+
+        if let Some(tag) = &tag {
+            attrs.prefix = Some(tag_type(tag, &attrs.parse_type));
+        }
+
+        Ok(Self { name, attrs, tag })
+    }
+
     pub fn fn_impl<'a>(&'a self, enum_: &'a Enum) -> FnImpl<'a, NamedField> {
-        let Self { name, attrs } = self;
+        let Self { name, attrs, .. } = self;
         let fields: &[NamedField] = &[];
         FnImpl {
             ty: "unit variant",
@@ -29,3 +57,14 @@ impl EnumVariantUnit {
         }
     }
 }
+
+/// Creates the `Tag` type matching `tag`, using [`nommy::bytes::Tag`] if the enum parses `u8`s
+fn tag_type(tag: &str, parse_type: &Option<syn::Type>) -> syn::Type {
+    match parse_type {
+        Some(syn::Type::Path(ty)) if ty.path.is_ident("u8") => {
+            let tag = syn::LitByteStr::new(tag.as_bytes(), proc_macro2::Span::call_site());
+            syn::parse_quote! { ::nommy::bytes::Tag<#tag> }
+        }
+        _ => syn::parse_quote! { ::nommy::text::Tag<#tag> },
+    }
+}
@@ -1,4 +1,4 @@
-use super::Enum;
+use super::{pratt::Operator, Enum};
 use crate::{
     attr::GlobalAttr,
     fn_impl::FnImpl,
@@ -11,6 +11,7 @@ pub struct EnumVariantUnnamed {
     pub name: syn::Ident,
     pub attrs: GlobalAttr,
     pub fields: Vec<UnnamedField>,
+    pub operator: Option<Operator>,
 }
 
 impl EnumVariantUnnamed {
@@ -19,6 +20,7 @@ impl EnumVariantUnnamed {
             name,
             attrs,
             fields,
+            ..
         } = self;
         FnImpl {
             ty: "tuple variant",
@@ -0,0 +1,126 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote_spanned};
+
+use super::{Enum, EnumVariant};
+use crate::{
+    attr::GlobalAttr,
+    parsers::{FieldType, NamedField},
+};
+
+/// The tag a variant must start with, if it can be determined from the attributes
+struct LeadingTag {
+    tag: Vec<u8>,
+    /// The variant parses nothing but this tag
+    exact: bool,
+}
+
+/// Returns the literal of a `Tag<"...">` or `Tag<b"...">` type
+pub fn tag_literal(ty: &syn::Type) -> Option<Vec<u8>> {
+    let path = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Tag" {
+        return None;
+    }
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => &args.args[0],
+        _ => return None,
+    };
+    let lit = match args {
+        syn::GenericArgument::Const(syn::Expr::Lit(lit)) => &lit.lit,
+        _ => return None,
+    };
+    match lit {
+        syn::Lit::Str(s) => Some(s.value().into_bytes()),
+        syn::Lit::ByteStr(s) => Some(s.value()),
+        _ => None,
+    }
+}
+
+fn leading_tag<F: FieldType>(attrs: &GlobalAttr, fields: &[F]) -> Option<LeadingTag> {
+    let mut fields = fields
+        .iter()
+        .filter(|f| !f.attrs().skip && f.attrs().default.is_none());
+
+    if let Some(prefix) = &attrs.prefix {
+        let tag = tag_literal(prefix)?;
+        let exact = attrs.suffix.is_none() && fields.next().is_none();
+        return Some(LeadingTag { tag, exact });
+    }
+
+    let field = fields.next()?;
+    let field_attrs = field.attrs();
+    if let Some(prefix) = &field_attrs.prefix {
+        return Some(LeadingTag {
+            tag: tag_literal(prefix)?,
+            exact: false,
+        });
+    }
+    if field_attrs.vec.is_some() {
+        return None;
+    }
+
+    let tag = tag_literal(field_attrs.parser.as_ref().unwrap_or_else(|| field.ty()))?;
+    let exact = field_attrs.suffix.is_none() && attrs.suffix.is_none() && fields.next().is_none();
+    Some(LeadingTag { tag, exact })
+}
+
+fn variant_leading_tag(variant: &EnumVariant) -> Option<LeadingTag> {
+    // operators are parsed apart from the other variants
+    if variant.operator().is_some() {
+        return None;
+    }
+    match variant {
+        EnumVariant::Named(v) => leading_tag(&v.attrs, &v.fields),
+        EnumVariant::Unnamed(v) => leading_tag(&v.attrs, &v.fields),
+        EnumVariant::Unit(v) => leading_tag::<NamedField>(&v.attrs, &[]),
+    }
+}
+
+/// Emits a warning for every variant that can never be parsed, because an earlier
+/// variant consists only of a tag that is a prefix of the variant's own leading tag.
+///
+/// Proc macros cannot emit warnings directly on stable,
+/// so this uses a deprecated item spanned to the unreachable variant.
+pub fn unreachable_warnings(enum_: &Enum) -> TokenStream {
+    let tags: Vec<_> = enum_.variants.iter().map(variant_leading_tag).collect();
+    let mut tokens = TokenStream::new();
+
+    for (j, later) in tags.iter().enumerate() {
+        let later = match later {
+            Some(later) => later,
+            None => continue,
+        };
+        let earlier = tags[..j]
+            .iter()
+            .enumerate()
+            .find_map(|(i, earlier)| match earlier {
+                Some(earlier) if earlier.exact && later.tag.starts_with(&earlier.tag) => Some(i),
+                _ => None,
+            });
+
+        if let Some(i) = earlier {
+            let name = enum_.variants[j].name();
+            let note = format!(
+                "variant `{}` of `{}` can never be parsed, variant `{}` always matches first. \
+                consider reordering the variants or using `#[nommy(longest_match)]`",
+                name,
+                enum_.name,
+                enum_.variants[i].name(),
+            );
+            let warning = format_ident!("__nommy_unreachable_variant_{}", name);
+            tokens.extend(quote_spanned! {name.span()=>
+                const _: () = {
+                    #[deprecated(note = #note)]
+                    #[allow(non_camel_case_types)]
+                    struct #warning;
+                    let _ = #warning;
+                };
+            });
+        }
+    }
+
+    tokens
+}
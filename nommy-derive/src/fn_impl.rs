@@ -22,6 +22,8 @@ pub struct Builder<'a> {
     parse_impl: TokenStream,
     wc: TokenStream,
     after_each: TokenStream,
+    longest_match: bool,
+    in_bits: bool,
 }
 
 impl<'a> Builder<'a> {
@@ -38,10 +40,13 @@ impl<'a> Builder<'a> {
             parse_impl: TokenStream::new(),
             wc: TokenStream::new(),
             after_each: TokenStream::new(),
+            longest_match: false,
+            in_bits: false,
         }
     }
 
-    pub fn build(self) -> BuildOutput {
+    pub fn build(mut self) -> BuildOutput {
+        self.realign();
         let Builder {
             peek_impl,
             parse_impl,
@@ -76,6 +81,7 @@ impl<'a> Builder<'a> {
 
     pub fn add_fix(&mut self, fix: &Option<syn::Type>, fix_type: &'static str, name: String) {
         if let Some(fix) = fix {
+            self.realign();
             self.add_where(&fix);
             self.parse_impl.extend(
                 self.parser_peek_tokens(
@@ -83,70 +89,207 @@ impl<'a> Builder<'a> {
                     &format!("failed to parse {} for {}", fix_type, name),
                 ),
             );
-            self.peek_impl.extend(self.peeker_peek_tokens(&fix));
+            self.peek_impl.extend(self.peeker_peek_tokens(fix, false));
             self.ignore();
         }
     }
 
+    /// Returns `Some(result)` from the enclosing fn if `op` comes next, consuming it
+    pub fn add_operator(&mut self, op: &syn::Type, result: TokenStream) {
+        self.add_where(op);
+        let generic = self.generic;
+        let after_each = &self.after_each;
+        self.parse_impl.extend(quote! {
+            {
+                let mut cursor = input.cursor();
+                if <#op as ::nommy::Parse<#generic>>::peek(&mut cursor) {
+                    let pos = cursor.position();
+                    input.fast_forward(pos);
+                    #after_each
+                    return Some(#result);
+                }
+            }
+        });
+    }
+
+    /// Adds the parsing of a field. Vec fields parse one level deeper in the buffer's context,
+    /// since a type can reach itself through one. `Box` already does the same when it's parsed
     pub fn add_field<F: FieldType>(&mut self, field: &F, field_num: usize) {
         let ty = field.ty();
         let name = field.name(field_num);
         let attrs = field.attrs();
 
+        if attrs.skip || attrs.default.is_some() {
+            self.parse_impl
+                .extend(self.parser_default_tokens(&name, &attrs.default));
+            return;
+        }
+
+        if let Some(bits) = &attrs.bits {
+            self.add_bits(&name, bits);
+            return;
+        }
+        self.realign();
+
         self.add_fix(&attrs.prefix, "prefix", format!("field `{}`", name));
 
-        if attrs.vec.is_some() {
-            let parser: Option<&syn::Type> = (&attrs.vec.parser).into();
-            let parser = parser.unwrap();
+        if let Some(parser) = &attrs.vec.parser {
             self.add_where(&parser);
+            if let Some(sep) = &attrs.vec.seperated_by {
+                self.add_where(sep);
+            }
             self.parse_impl
-                .extend(self.parser_parse_vec_tokens(&name, &attrs.vec));
-            self.peek_impl.extend(self.peeker_peek_vec_tokens(&parser));
+                .extend(self.parser_parse_vec_tokens(&name, parser, &attrs.vec));
+            self.peek_impl
+                .extend(self.peeker_peek_vec_tokens(parser, &attrs.vec));
         } else {
             let parser: Option<&syn::Type> = (&attrs.parser).into();
             let parser = parser.unwrap_or(&ty);
             self.add_where(&parser);
+            let nested = crate::ty::is_vec(parser);
             self.parse_impl.extend(self.parser_parse_tokens(
                 &name,
                 &parser,
                 &format!("failed to parse field `{}`", name),
+                nested,
             ));
-            self.peek_impl.extend(self.peeker_peek_tokens(&parser));
+            self.peek_impl
+                .extend(self.peeker_peek_tokens(parser, nested));
             self.ignore();
         }
 
         self.add_fix(&attrs.suffix, "suffix", format!("field `{}`", name));
     }
 
-    pub fn start_variants(&mut self) {
-        self.parse_impl.extend(quote! {
-            let mut cursor = input.cursor();
-            let result =
-        });
-        self.peek_impl
-            .extend(quote! { let mut cursor = input.cursor(); if });
-    }
-    pub fn add_variant(&mut self, peek_name: &syn::Ident, parse_name: &syn::Ident) {
+    /// Reads a `bits = N` field out of the bytes following the previous bit field.
+    /// The bytes are only consumed once the bit fields end, see [`Builder::realign`]
+    fn add_bits(&mut self, name: &syn::Ident, bits: &syn::Expr) {
+        if !self.in_bits {
+            self.in_bits = true;
+            let start = quote! { let mut __nommy_bits = 0usize; };
+            self.parse_impl.extend(start.clone());
+            self.peek_impl.extend(start);
+        }
+
+        let error = format!("failed to parse field `{}`", name);
         self.parse_impl.extend(quote! {
-            if let (true, Ok(result)) = (cursor.reset_internal(), Self::#parse_name(&mut cursor)) {
+            let #name = {
+                let mut bits = ::nommy::bytes::BitBuffer::with_offset(input, __nommy_bits);
+                let result = <::nommy::bytes::Bits<#bits> as ::nommy::Parse<bool>>::parse(&mut bits).wrap_err(#error)?;
+                __nommy_bits = bits.offset();
                 result
-            } else
+            }.try_into()?;
         });
         self.peek_impl.extend(quote! {
-            !Self::#peek_name(&mut cursor) && cursor.reset_internal() &&
+            {
+                let mut bits = ::nommy::bytes::BitBuffer::with_offset(input, __nommy_bits);
+                if !<::nommy::bytes::Bits<#bits> as ::nommy::Parse<bool>>::peek(&mut bits) { return false }
+                __nommy_bits = bits.offset();
+            }
         });
     }
-    pub fn finish_variants(&mut self, error: String) {
+
+    /// Consumes the bytes read by the preceding bit fields, erroring if they didn't end on a byte boundary
+    fn realign(&mut self) {
+        if !self.in_bits {
+            return;
+        }
+        self.in_bits = false;
+
+        let error = format!(
+            "bit fields of `{}` do not end on a byte boundary",
+            self.type_name
+        );
         self.parse_impl.extend(quote! {
-            { return Err(::nommy::eyre::eyre!(#error)); };
-            let pos = cursor.position();
-            input.fast_forward(pos);
+            ::nommy::bytes::BitBuffer::with_offset(input, __nommy_bits).realign().wrap_err(#error)?;
         });
         self.peek_impl.extend(quote! {
-            true { return false; }
-            let pos = cursor.position();
-            input.fast_forward(pos);
+            if ::nommy::bytes::BitBuffer::with_offset(input, __nommy_bits).realign().is_err() { return false }
         });
+        self.ignore();
+    }
+
+    pub fn start_variants(&mut self, longest_match: bool) {
+        self.longest_match = longest_match;
+        if longest_match {
+            self.parse_impl.extend(quote! {
+                let mut best: ::std::option::Option<(usize, Self)> = None;
+            });
+            self.peek_impl.extend(quote! {
+                let mut best: ::std::option::Option<usize> = None;
+            });
+        } else {
+            self.parse_impl.extend(quote! {
+                let mut cursor = input.cursor();
+                let result =
+            });
+            self.peek_impl
+                .extend(quote! { let mut cursor = input.cursor(); if });
+        }
+    }
+    pub fn add_variant(&mut self, peek_name: &syn::Ident, parse_name: &syn::Ident) {
+        if self.longest_match {
+            // ties go to the variant declared first
+            self.parse_impl.extend(quote! {
+                {
+                    let mut cursor = input.cursor();
+                    if let Ok(result) = Self::#parse_name(&mut cursor) {
+                        let pos = cursor.position();
+                        if best.as_ref().map_or(true, |(best, _)| pos > *best) {
+                            best = Some((pos, result));
+                        }
+                    }
+                }
+            });
+            self.peek_impl.extend(quote! {
+                {
+                    let mut cursor = input.cursor();
+                    if Self::#peek_name(&mut cursor) {
+                        let pos = cursor.position();
+                        if best.map_or(true, |best| pos > best) {
+                            best = Some(pos);
+                        }
+                    }
+                }
+            });
+        } else {
+            self.parse_impl.extend(quote! {
+                if let (true, Ok(result)) = (cursor.reset_internal(), Self::#parse_name(&mut cursor)) {
+                    result
+                } else
+            });
+            self.peek_impl.extend(quote! {
+                !Self::#peek_name(&mut cursor) && cursor.reset_internal() &&
+            });
+        }
+    }
+    pub fn finish_variants(&mut self, error: String) {
+        if self.longest_match {
+            self.parse_impl.extend(quote! {
+                let (pos, result) = match best {
+                    Some(best) => best,
+                    None => return Err(::nommy::eyre::eyre!(#error)),
+                };
+                input.fast_forward(pos);
+            });
+            self.peek_impl.extend(quote! {
+                match best {
+                    Some(pos) => input.fast_forward(pos),
+                    None => return false,
+                }
+            });
+        } else {
+            self.parse_impl.extend(quote! {
+                { return Err(::nommy::eyre::eyre!(#error)); };
+                let pos = cursor.position();
+                input.fast_forward(pos);
+            });
+            self.peek_impl.extend(quote! {
+                true { return false; }
+                let pos = cursor.position();
+                input.fast_forward(pos);
+            });
+        }
     }
 }
 
@@ -183,6 +326,116 @@ impl<'a, F: FieldType> FnImpl<'a, F> {
     }
 }
 
+/// Creates the `impl ... Parse<T> for Type<...> where ...` line, keeping the type's own
+/// lifetimes, const generics, bounds and where clause.
+/// If no `parse_type` was given, `generic` is added as an extra type parameter and `wc` is merged
+/// into the where clause
+pub fn impl_line(
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    parse_type: &Option<syn::Type>,
+    generic: &syn::Type,
+    wc: &TokenStream,
+) -> TokenStream {
+    trait_impl_line(
+        quote! { ::nommy::Parse },
+        name,
+        generics,
+        parse_type,
+        generic,
+        wc,
+    )
+}
+
+/// Same as [`impl_line`], but for any trait generic over the input type, such as `Print<T>`
+pub fn trait_impl_line(
+    trait_name: TokenStream,
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    parse_type: &Option<syn::Type>,
+    generic: &syn::Type,
+    wc: &TokenStream,
+) -> TokenStream {
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    match parse_type {
+        Some(_) => {
+            let (impl_generics, _, _) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics #trait_name<#generic> for #name #ty_generics #where_clause
+            }
+        }
+        None => {
+            let generics = with_parse_generic(generics);
+            let (impl_generics, _, _) = generics.split_for_impl();
+            let predicates = where_clause.into_iter().flat_map(|wc| &wc.predicates);
+            quote! {
+                impl #impl_generics #trait_name<#generic> for #name #ty_generics where #(#predicates,)* #wc
+            }
+        }
+    }
+}
+
+/// Wraps the bodies of `parse` and `peek` with `::nommy::memo` if the type has `#[nommy(memoize)]`,
+/// so it's parsed at most once at each position of the input
+pub fn memoize(
+    attrs: &GlobalAttr,
+    wc: &mut TokenStream,
+    parse: TokenStream,
+    peek: TokenStream,
+) -> (TokenStream, TokenStream) {
+    if !attrs.memoize {
+        return (parse, peek);
+    }
+    wc.extend(quote! {
+        Self: ::std::clone::Clone + ::std::marker::Send + ::std::marker::Sync + 'static,
+    });
+    // `input` is a concrete cursor inside the closures, so its methods need the trait in scope
+    (
+        quote! {
+            ::nommy::memo::parse(input, |input| {
+                #[allow(unused_imports)]
+                use ::nommy::Buffer as _;
+                #parse
+            })
+        },
+        quote! {
+            ::nommy::memo::peek::<Self, _, _>(input, |input| {
+                #[allow(unused_imports)]
+                use ::nommy::Buffer as _;
+                #peek
+            })
+        },
+    )
+}
+
+/// Adds `__ParseGenericType` to the generics, after any lifetimes
+fn with_parse_generic(generics: &syn::Generics) -> syn::Generics {
+    let mut generics = generics.clone();
+    let pos = generics.lifetimes().count();
+    generics
+        .params
+        .insert(pos, syn::parse_quote! { __ParseGenericType });
+    generics
+}
+
+/// Runs the parse within the buffer's context, so that it fails once the context has interrupted it.
+/// Derived types don't count towards the depth limit themselves, since most of them can't reach themselves.
+/// Types can only nest through a `Box` or a vec, so those fields count instead, see [`Builder::add_field`]
+pub fn in_context(parse: TokenStream, peek: TokenStream) -> (TokenStream, TokenStream) {
+    (
+        quote! {
+            ::nommy::context::check(input, |input| {
+                #parse
+            })
+        },
+        quote! {
+            ::nommy::context::check_peek(input, |input| {
+                #peek
+            })
+        },
+    )
+}
+
 pub fn parse_or(parse_type: &Option<syn::Type>) -> syn::Type {
     match &parse_type {
         Some(pt) => pt.clone(),
@@ -219,86 +472,101 @@ impl<'a> Builder<'a> {
             if !(<#ty as ::nommy::Parse<#generic>>::peek(input)) { return Err(::nommy::eyre::eyre!(#error)) }
         }
     }
-    fn parser_parse_tokens(&self, name: &syn::Ident, ty: &syn::Type, error: &str) -> TokenStream {
+    fn parser_parse_tokens(
+        &self,
+        name: &syn::Ident,
+        ty: &syn::Type,
+        error: &str,
+        nested: bool,
+    ) -> TokenStream {
         let generic = &self.generic;
+        let parse = quote! { <#ty as ::nommy::Parse<#generic>>::parse };
+        let parse = if nested {
+            quote! { ::nommy::context::parse(input, #parse) }
+        } else {
+            quote! { #parse(input) }
+        };
         quote! {
-            let #name = <#ty as ::nommy::Parse<#generic>>::parse(input).wrap_err(#error)?.try_into()?;
+            let #name = #parse.wrap_err(#error)?.try_into()?;
         }
     }
-    fn peeker_peek_tokens(&self, ty: &syn::Type) -> TokenStream {
+    fn parser_default_tokens(&self, name: &syn::Ident, default: &Option<syn::Expr>) -> TokenStream {
+        match default {
+            Some(default) => quote! {
+                let #name = #default;
+            },
+            None => quote! {
+                let #name = ::std::default::Default::default();
+            },
+        }
+    }
+    fn peeker_peek_tokens(&self, ty: &syn::Type, nested: bool) -> TokenStream {
         let generic = &self.generic;
+        let peek = quote! { <#ty as ::nommy::Parse<#generic>>::peek };
+        let peek = if nested {
+            quote! { ::nommy::context::peek(input, #peek) }
+        } else {
+            quote! { #peek(input) }
+        };
         quote! {
-            if !(<#ty as ::nommy::Parse<#generic>>::peek(input)) { return false }
+            if !(#peek) { return false }
         }
     }
 
-    fn parser_parse_vec_tokens(&self, name: &syn::Ident, attrs: &VecFieldAttr) -> TokenStream {
+    fn parser_parse_vec_tokens(
+        &self,
+        name: &syn::Ident,
+        parser: &syn::Type,
+        attrs: &VecFieldAttr,
+    ) -> TokenStream {
         let generic = &self.generic;
+        let (min, max) = vec_bounds(attrs);
 
-        let parser: Option<&syn::Type> = (&attrs.parser).into();
-        let parser = parser.unwrap();
-
-        let (min, max) = match &attrs.count {
-            Some(count) => (quote! { #count }, quote! { #count }),
-            None => {
-                let min = match &attrs.min {
-                    Some(min) => quote! { #min },
-                    None => quote! { 0 },
-                };
-                let max = match &attrs.max {
-                    Some(max) => quote! { #max },
-                    None => quote! { usize::MAX },
-                };
-                (min, max)
-            }
+        let parse_vec = match (&attrs.seperated_by, &attrs.trailing) {
+            (Some(sep), Some(true)) => quote! {
+                ::nommy::vec::parse_vec_seperated_by_trailing::<#parser, _, #sep, __ParseIgnore, #generic, _>
+            },
+            (Some(sep), Some(false)) => quote! {
+                ::nommy::vec::parse_vec_seperated_by_maybe_trailing::<#parser, _, #sep, __ParseIgnore, #generic, _>
+            },
+            (Some(sep), None) => quote! {
+                ::nommy::vec::parse_vec_seperated_by::<#parser, _, #sep, __ParseIgnore, #generic, _>
+            },
+            (None, _) => quote! {
+                ::nommy::vec::parse_vec::<#parser, _, __ParseIgnore, #generic, _>
+            },
         };
 
-        if let Some(sep) = &attrs.seperated_by {
-            match &attrs.trailing {
-                Some(true) => quote! {
-                    let #name = ::nommy::vec::parse_vec_seperated_by_trailing::<#parser, _, #sep, __ParseIgnore, #generic, _>(#max, input)?;
-                    if #name.len() < #min {
-                        return Err(::nommy::eyre::eyre!("could not parse enough for vec"));
-                    }
-                },
-                Some(false) => quote! {
-                    let #name = ::nommy::vec::parse_vec_seperated_by_maybe_trailing::<#parser, _, #sep, __ParseIgnore, #generic, _>(#max, input)?;
-                    if #name.len() < #min {
-                        return Err(::nommy::eyre::eyre!("could not parse enough for vec"));
-                    }
-                },
-                None => quote! {
-                    let #name = ::nommy::vec::parse_vec_seperated_by::<#parser, _, #sep, __ParseIgnore, #generic, _>(#max, input)?;
-                    if #name.len() < #min {
-                        return Err(::nommy::eyre::eyre!("could not parse enough for vec"));
-                    }
-                },
-            }
-        } else {
-            quote! {
-                let #name = ::nommy::vec::parse_vec::<#parser, _, __ParseIgnore, #generic, _>(#max, input)?;
-                if #name.len() < #min {
-                    return Err(::nommy::eyre::eyre!("could not parse enough for vec"));
-                }
+        quote! {
+            let #name = ::nommy::context::parse(input, |input| #parse_vec(#max, input))?;
+            if #name.len() < #min {
+                return Err(::nommy::eyre::eyre!("could not parse enough for vec"));
             }
         }
     }
 
-    pub fn peeker_peek_vec_tokens(&self, ty: &syn::Type) -> TokenStream {
+    /// Peeks the same input as [`Builder::parser_parse_vec_tokens`] parses
+    fn peeker_peek_vec_tokens(&self, parser: &syn::Type, attrs: &VecFieldAttr) -> TokenStream {
         let generic = &self.generic;
-        let after_each = &self.after_each;
+        let (min, max) = vec_bounds(attrs);
 
-        quote! {
-            loop {
-                let mut cursor = input.cursor();
-                if !<#ty as ::nommy::Parse<#generic>>::peek(&mut cursor) {
-                    break;
-                }
-                let pos = cursor.position();
-                input.fast_forward(pos);
+        let peek_vec = match (&attrs.seperated_by, &attrs.trailing) {
+            (Some(sep), Some(true)) => quote! {
+                ::nommy::vec::peek_vec_seperated_by_trailing::<#parser, #sep, __ParseIgnore, #generic, _>
+            },
+            (Some(sep), Some(false)) => quote! {
+                ::nommy::vec::peek_vec_seperated_by_maybe_trailing::<#parser, #sep, __ParseIgnore, #generic, _>
+            },
+            (Some(sep), None) => quote! {
+                ::nommy::vec::peek_vec_seperated_by::<#parser, #sep, __ParseIgnore, #generic, _>
+            },
+            (None, _) => quote! {
+                ::nommy::vec::peek_vec::<#parser, __ParseIgnore, #generic, _>
+            },
+        };
 
-                #after_each
-            }
+        quote! {
+            if !::nommy::context::peek(input, |input| #peek_vec(#max, input) >= #min) { return false }
         }
     }
 
@@ -356,3 +624,21 @@ impl<'a> Builder<'a> {
         (ignore_impl, after_each)
     }
 }
+
+/// The fewest and most values a vec field can have
+fn vec_bounds(attrs: &VecFieldAttr) -> (TokenStream, TokenStream) {
+    match &attrs.count {
+        Some(count) => (quote! { #count }, quote! { #count }),
+        None => {
+            let min = match &attrs.min {
+                Some(min) => quote! { #min },
+                None => quote! { 0 },
+            };
+            let max = match &attrs.max {
+                Some(max) => quote! { #max },
+                None => quote! { usize::MAX },
+            };
+            (min, max)
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{attr::VecFieldAttr, parsers::FieldType, walker::Backend};
+
+/// Builds the body of `Generate::generate`, writing out random input in the same order that [`Builder`](crate::fn_impl::Builder) parses it.
+/// Only the first ignore type is generated, wherever the parser would skip over ignored values
+pub struct Generator;
+
+impl Backend for Generator {
+    fn trait_path() -> TokenStream {
+        quote! { ::nommy::Generate }
+    }
+
+    fn fix(generic: &syn::Type, ty: &syn::Type) -> TokenStream {
+        quote! { <#ty as ::nommy::Generate<#generic>>::generate(gen, output); }
+    }
+
+    fn bits(key: &proc_macro2::Ident, _bits: &syn::Expr) -> syn::Result<TokenStream> {
+        Err(syn::Error::new_spanned(
+            key,
+            "`bits` fields cannot be generated",
+        ))
+    }
+
+    fn vec(
+        _name: &syn::Ident,
+        elem: TokenStream,
+        between: TokenStream,
+        attrs: &VecFieldAttr,
+    ) -> TokenStream {
+        let count = match &attrs.count {
+            Some(count) => quote! { #count },
+            None => {
+                let min = match &attrs.min {
+                    Some(min) => quote! { #min },
+                    None => quote! { 0 },
+                };
+                let max = match &attrs.max {
+                    Some(max) => quote! { #max },
+                    None => quote! { usize::MAX },
+                };
+                quote! { gen.repeat_between(#min, #max) }
+            }
+        };
+
+        let trailing = match (&attrs.seperated_by, attrs.trailing) {
+            (Some(_), Some(true)) => quote! { count > 0 },
+            (Some(_), Some(false)) => quote! { count > 0 && gen.rng().gen_bool() },
+            _ => quote! { false },
+        };
+        quote! {
+            {
+                let count: usize = #count;
+                for i in 0..count {
+                    if i > 0 {
+                        #between
+                    }
+                    #elem
+                }
+                if #trailing {
+                    #between
+                }
+            }
+        }
+    }
+
+    fn join(parts: Vec<TokenStream>) -> TokenStream {
+        quote! { #(#parts)* }
+    }
+
+    fn method(
+        generic: &syn::Type,
+        _pattern: Option<TokenStream>,
+        body: TokenStream,
+    ) -> TokenStream {
+        quote! {
+            #[allow(unused_variables)]
+            fn generate(gen: &mut ::nommy::Generator, output: &mut impl ::std::iter::Extend<#generic>) {
+                gen.nested(|gen| {
+                    #body
+                });
+            }
+        }
+    }
+}
+
+/// Whether any of the fields parse the type itself, either directly or inside another type.
+/// Such enum variants aren't generated once the max depth is reached
+pub fn is_recursive<F: FieldType>(fields: &[F], type_name: &syn::Ident) -> bool {
+    fields.iter().any(|field| {
+        let attrs = field.attrs();
+        let parser: Option<&syn::Type> = (&attrs.parser).into();
+        let inner_parser: Option<&syn::Type> = (&attrs.vec.parser).into();
+        let ty = inner_parser.or(parser).unwrap_or_else(|| field.ty());
+        !attrs.skip && attrs.default.is_none() && crate::ty::contains(ty, type_name)
+    })
+}
@@ -1,18 +1,39 @@
 use proc_macro::TokenStream;
-use quote::ToTokens;
-use syn::{parse_macro_input, spanned::Spanned, DeriveInput};
+use syn::{parse_macro_input, DeriveInput};
 
 mod attr;
+mod case;
+mod describe_impl;
 mod enum_impl;
 mod fn_impl;
+mod generate_impl;
 mod parsers;
+mod print_impl;
 mod struct_impl;
 mod ty;
+mod walker;
 
-#[proc_macro_derive(Parse, attributes(nommy))]
-pub fn derive_parse(input: TokenStream) -> TokenStream {
+/// A struct or enum that the traits can be derived for
+trait Item {
+    /// Implements `Parse`
+    fn parse_tokens(&self) -> proc_macro2::TokenStream;
+
+    /// Implements `Print`, writing out the same input that `Parse` parses
+    fn print_tokens(&self) -> syn::Result<proc_macro2::TokenStream>;
+
+    /// Implements `Generate`, writing out random input that `Parse` can parse
+    fn generate_tokens(&self) -> syn::Result<proc_macro2::TokenStream>;
+
+    /// Implements `Describe`, describing the grammar that `Parse` accepts
+    fn describe_tokens(&self) -> syn::Result<proc_macro2::TokenStream>;
+}
+
+/// Parses the type that's being derived, and implements a trait for it with `f`
+fn derive_with(
+    input: TokenStream,
+    f: impl Fn(&dyn Item) -> syn::Result<proc_macro2::TokenStream>,
+) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let span = input.span();
     let DeriveInput {
         attrs,
         vis: _,
@@ -21,32 +42,44 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
         data,
     } = input;
 
-    match data {
+    let output = match data {
         syn::Data::Struct(s) => match s.fields {
             syn::Fields::Named(fields) => {
-                match struct_impl::Named::new(ident, generics, attrs, fields) {
-                    Ok(t) => t.into_token_stream(),
-                    Err(e) => e.to_compile_error(),
-                }
+                struct_impl::Named::new(ident, generics, attrs, fields).and_then(|t| f(&t))
             }
             syn::Fields::Unnamed(fields) => {
-                match struct_impl::Unnamed::new(ident, generics, attrs, fields) {
-                    Ok(t) => t.into_token_stream(),
-                    Err(e) => e.to_compile_error(),
-                }
+                struct_impl::Unnamed::new(ident, generics, attrs, fields).and_then(|t| f(&t))
             }
-            syn::Fields::Unit => match struct_impl::Unit::new(ident, generics, attrs) {
-                Ok(t) => t.into_token_stream(),
-                Err(e) => e.to_compile_error(),
-            },
+            syn::Fields::Unit => struct_impl::Unit::new(ident, generics, attrs).and_then(|t| f(&t)),
         },
         syn::Data::Enum(enum_data) => {
-            match enum_impl::Enum::new(ident, generics, attrs, enum_data) {
-                Ok(t) => t.into_token_stream(),
-                Err(e) => e.to_compile_error(),
-            }
+            enum_impl::Enum::new(ident, generics, attrs, enum_data).and_then(|t| f(&t))
         }
-        syn::Data::Union(_) => syn::Error::new(span, "unions not supported").into_compile_error(),
-    }
-    .into()
+        syn::Data::Union(u) => Err(syn::Error::new_spanned(
+            u.union_token,
+            "unions not supported",
+        )),
+    };
+
+    output.unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+#[proc_macro_derive(Parse, attributes(nommy))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    derive_with(input, |item| Ok(item.parse_tokens()))
+}
+
+#[proc_macro_derive(Print, attributes(nommy))]
+pub fn derive_print(input: TokenStream) -> TokenStream {
+    derive_with(input, |item| item.print_tokens())
+}
+
+#[proc_macro_derive(Generate, attributes(nommy))]
+pub fn derive_generate(input: TokenStream) -> TokenStream {
+    derive_with(input, |item| item.generate_tokens())
+}
+
+#[proc_macro_derive(Describe, attributes(nommy))]
+pub fn derive_describe(input: TokenStream) -> TokenStream {
+    derive_with(input, |item| item.describe_tokens())
 }
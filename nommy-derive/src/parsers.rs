@@ -43,6 +43,7 @@ pub trait FieldType {
     fn ty(&self) -> &syn::Type;
     fn name(&self, i: usize) -> syn::Ident;
     fn attrs(&self) -> &FieldAttr;
+    fn attrs_mut(&mut self) -> &mut FieldAttr;
 }
 
 impl FieldType for NamedField {
@@ -55,6 +56,9 @@ impl FieldType for NamedField {
     fn attrs(&self) -> &FieldAttr {
         &self.attrs
     }
+    fn attrs_mut(&mut self) -> &mut FieldAttr {
+        &mut self.attrs
+    }
 }
 
 impl FieldType for UnnamedField {
@@ -67,4 +71,25 @@ impl FieldType for UnnamedField {
     fn attrs(&self) -> &FieldAttr {
         &self.attrs
     }
+    fn attrs_mut(&mut self) -> &mut FieldAttr {
+        &mut self.attrs
+    }
+}
+
+/// Errors if any field uses `bits`, unless the type parses `u8`s
+pub fn check_bits<F: FieldType>(parse_type: &Option<syn::Type>, fields: &[F]) -> syn::Result<()> {
+    if let Some(syn::Type::Path(ty)) = parse_type {
+        if ty.path.is_ident("u8") {
+            return Ok(());
+        }
+    }
+    for field in fields {
+        if let Some(key) = field.attrs().keys.iter().find(|k| *k == "bits") {
+            return Err(syn::Error::new_spanned(
+                key,
+                "`bits` requires `#[nommy(parse_type = u8)]`",
+            ));
+        }
+    }
+    Ok(())
 }
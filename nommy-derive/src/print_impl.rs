@@ -0,0 +1,94 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{attr::VecFieldAttr, walker::Backend};
+
+/// Builds the body of `Print::print`, writing out values in the same order that [`Builder`](crate::fn_impl::Builder) parses them.
+/// Only the first ignore type is printed, wherever the parser would skip over ignored values
+pub struct Printer;
+
+impl Backend for Printer {
+    fn trait_path() -> TokenStream {
+        quote! { ::nommy::Print }
+    }
+
+    fn fix_bound(generic: &syn::Type, ty: &syn::Type) -> TokenStream {
+        quote! { #ty: ::std::default::Default + ::nommy::Print<#generic>, }
+    }
+
+    fn fix(generic: &syn::Type, ty: &syn::Type) -> TokenStream {
+        quote! { ::nommy::print_default::<#ty, #generic>(output); }
+    }
+
+    fn field_bound(generic: &syn::Type, ty: &syn::Type, parser: Option<&syn::Type>) -> TokenStream {
+        match parser {
+            Some(parser) => quote! {
+                #parser: ::nommy::Print<#generic> + ::std::convert::From<#ty>,
+                #ty: ::std::clone::Clone,
+            },
+            None => Self::bound(generic, ty),
+        }
+    }
+
+    /// Fields with a parser are converted back into it and printed by it,
+    /// since the field type might print differently, or not at all
+    fn value(
+        generic: &syn::Type,
+        ty: &syn::Type,
+        parser: Option<&syn::Type>,
+        name: &syn::Ident,
+    ) -> TokenStream {
+        match parser {
+            Some(parser) => {
+                quote! { ::nommy::print_with::<#parser, #ty, #generic>(#name, output); }
+            }
+            None => quote! { ::nommy::Print::<#generic>::print(#name, output); },
+        }
+    }
+
+    fn bits(key: &proc_macro2::Ident, _bits: &syn::Expr) -> syn::Result<TokenStream> {
+        Err(syn::Error::new_spanned(
+            key,
+            "`bits` fields cannot be printed",
+        ))
+    }
+
+    fn vec(
+        name: &syn::Ident,
+        elem: TokenStream,
+        between: TokenStream,
+        attrs: &VecFieldAttr,
+    ) -> TokenStream {
+        match (&attrs.seperated_by, attrs.trailing) {
+            (Some(_), Some(true)) => quote! {
+                for elem in #name.iter() {
+                    #elem
+                    #between
+                }
+            },
+            _ => quote! {
+                for (i, elem) in #name.iter().enumerate() {
+                    if i > 0 {
+                        #between
+                    }
+                    #elem
+                }
+            },
+        }
+    }
+
+    fn join(parts: Vec<TokenStream>) -> TokenStream {
+        quote! { #(#parts)* }
+    }
+
+    fn method(generic: &syn::Type, pattern: Option<TokenStream>, body: TokenStream) -> TokenStream {
+        let pattern = pattern.map(|pattern| quote! { let #pattern = self; });
+        quote! {
+            #[allow(unused_variables)]
+            fn print(&self, output: &mut impl ::std::iter::Extend<#generic>) {
+                #pattern
+                #body
+            }
+        }
+    }
+}
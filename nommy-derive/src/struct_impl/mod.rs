@@ -5,3 +5,25 @@ mod unnamed;
 pub use named::Named;
 pub use unit::Unit;
 pub use unnamed::Unnamed;
+
+use proc_macro2::TokenStream;
+
+use crate::{
+    attr::GlobalAttr,
+    parsers::FieldType,
+    walker::{trait_impl, Backend, Walker},
+};
+
+/// Implements the trait of `B` for a struct. `pattern` destructures `self` into the field names
+pub fn walk_tokens<B: Backend, F: FieldType>(
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    attrs: &GlobalAttr,
+    generic: &syn::Type,
+    fields: &[F],
+    pattern: Option<TokenStream>,
+) -> syn::Result<TokenStream> {
+    let (body, wc) = Walker::<B>::fields(generic, name, attrs, fields)?;
+    let method = B::method(generic, pattern, body);
+    Ok(trait_impl::<B>(name, generics, attrs, generic, &wc, method))
+}
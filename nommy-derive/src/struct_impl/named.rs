@@ -3,16 +3,21 @@ use std::convert::TryInto;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
+use super::walk_tokens;
 use crate::{
     attr::GlobalAttr,
-    fn_impl::{parse_or, BuildOutput, FnImpl},
-    parsers::NamedField,
+    describe_impl::Describer,
+    fn_impl::{impl_line, in_context, memoize, parse_or, BuildOutput, FnImpl},
+    generate_impl::Generator,
+    parsers::{check_bits, NamedField},
+    print_impl::Printer,
+    Item,
 };
 
 pub struct Named {
     name: syn::Ident,
     fields: Vec<NamedField>,
-    args: Vec<syn::Ident>,
+    generics: syn::Generics,
     attrs: GlobalAttr,
     generic: syn::Type,
 }
@@ -22,7 +27,7 @@ impl ToTokens for Named {
         let Named {
             name,
             fields,
-            args,
+            generics,
             attrs,
             generic,
         } = self;
@@ -38,42 +43,86 @@ impl ToTokens for Named {
         let BuildOutput {
             peek_impl,
             parse_impl,
-            wc,
+            mut wc,
         } = fn_impl.build(&name);
 
         let names = self.fields.iter().map(|f| &f.name);
 
-        let impl_line = match attrs.parse_type {
-            Some(_) => quote!{
-                impl<#(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*>
+        let (parse_impl, peek_impl) = in_context(
+            quote! {
+                use ::nommy::eyre::WrapErr;
+                use ::std::convert::TryInto;
+                #parse_impl
+
+                Ok(#name {#(
+                    #names,
+                )*})
             },
-            None => quote!{
-                impl<#generic, #(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*> where #wc
+            quote! {
+                #peek_impl
+                true
             },
-        };
+        );
+        let (parse_body, peek_body) = memoize(attrs, &mut wc, parse_impl, peek_impl);
+
+        let impl_line = impl_line(name, generics, &attrs.parse_type, generic, &wc);
 
         tokens.extend(quote!{
             #[automatically_derived]
             #impl_line {
                 fn parse(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> {
-                    use ::nommy::eyre::WrapErr;
-                    use ::std::convert::TryInto;
-                    #parse_impl
-
-                    Ok(#name {#(
-                        #names,
-                    )*})
+                    #parse_body
                 }
 
                 fn peek(input: &mut impl ::nommy::Buffer<#generic>) -> bool {
-                    #peek_impl
-                    true
+                    #peek_body
                 }
             }
         });
     }
 }
 
+impl Item for Named {
+    fn parse_tokens(&self) -> TokenStream {
+        self.to_token_stream()
+    }
+
+    fn print_tokens(&self) -> syn::Result<TokenStream> {
+        let names = self.fields.iter().map(|f| &f.name);
+        let pattern = quote! { Self { #(#names,)* } };
+        walk_tokens::<Printer, _>(
+            &self.name,
+            &self.generics,
+            &self.attrs,
+            &self.generic,
+            &self.fields,
+            Some(pattern),
+        )
+    }
+
+    fn generate_tokens(&self) -> syn::Result<TokenStream> {
+        walk_tokens::<Generator, _>(
+            &self.name,
+            &self.generics,
+            &self.attrs,
+            &self.generic,
+            &self.fields,
+            None,
+        )
+    }
+
+    fn describe_tokens(&self) -> syn::Result<TokenStream> {
+        walk_tokens::<Describer, _>(
+            &self.name,
+            &self.generics,
+            &self.attrs,
+            &self.generic,
+            &self.fields,
+            None,
+        )
+    }
+}
+
 impl Named {
     pub fn new(
         name: syn::Ident,
@@ -81,19 +130,20 @@ impl Named {
         attrs: Vec<syn::Attribute>,
         fields: syn::FieldsNamed,
     ) -> syn::Result<Self> {
-        let args = generics.type_params().cloned().map(|tp| tp.ident).collect();
-        let fields = fields
+        let fields: Vec<NamedField> = fields
             .named
             .into_iter()
             .map(|f| f.try_into())
             .collect::<syn::Result<_>>()?;
         let attrs = GlobalAttr::parse_attrs(attrs)?;
+        attrs.check_struct()?;
+        check_bits(&attrs.parse_type, &fields)?;
         let generic = parse_or(&attrs.parse_type);
 
         Ok(Named {
             attrs,
             name,
-            args,
+            generics,
             fields,
             generic,
         })
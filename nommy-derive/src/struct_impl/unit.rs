@@ -1,15 +1,20 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
+use super::walk_tokens;
 use crate::{
     attr::GlobalAttr,
-    fn_impl::{parse_or, BuildOutput, FnImpl},
+    describe_impl::Describer,
+    fn_impl::{impl_line, in_context, memoize, parse_or, BuildOutput, FnImpl},
+    generate_impl::Generator,
     parsers::NamedField,
+    print_impl::Printer,
+    Item,
 };
 
 pub struct Unit {
     name: syn::Ident,
-    args: Vec<syn::Ident>,
+    generics: syn::Generics,
     attrs: GlobalAttr,
     generic: syn::Type,
 }
@@ -18,7 +23,7 @@ impl ToTokens for Unit {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Unit {
             name,
-            args,
+            generics,
             attrs,
             generic,
         } = self;
@@ -35,51 +40,96 @@ impl ToTokens for Unit {
         let BuildOutput {
             peek_impl,
             parse_impl,
-            wc,
+            mut wc,
         } = fn_impl.build(&name);
 
-        let impl_line = match attrs.parse_type {
-            Some(_) => quote!{
-                impl<#(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*>
+        let (parse_impl, peek_impl) = in_context(
+            quote! {
+                use ::nommy::eyre::WrapErr;
+                use ::std::convert::TryInto;
+                #parse_impl
+                Ok(#name)
             },
-            None => quote!{
-                impl<#generic, #(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*> where #wc
+            quote! {
+                #peek_impl
+                true
             },
-        };
+        );
+        let (parse_body, peek_body) = memoize(attrs, &mut wc, parse_impl, peek_impl);
+
+        let impl_line = impl_line(name, generics, &attrs.parse_type, generic, &wc);
 
         tokens.extend(quote!{
             #[automatically_derived]
             #impl_line {
                 fn parse(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> {
-                    use ::nommy::eyre::WrapErr;
-                    use ::std::convert::TryInto;
-                    #parse_impl
-                    Ok(#name)
+                    #parse_body
                 }
 
                 fn peek(input: &mut impl ::nommy::Buffer<#generic>) -> bool {
-                    #peek_impl
-                    true
+                    #peek_body
                 }
             }
         })
     }
 }
 
+impl Item for Unit {
+    fn parse_tokens(&self) -> TokenStream {
+        self.to_token_stream()
+    }
+
+    fn print_tokens(&self) -> syn::Result<TokenStream> {
+        let fields: &[NamedField] = &[];
+        walk_tokens::<Printer, _>(
+            &self.name,
+            &self.generics,
+            &self.attrs,
+            &self.generic,
+            fields,
+            None,
+        )
+    }
+
+    fn generate_tokens(&self) -> syn::Result<TokenStream> {
+        let fields: &[NamedField] = &[];
+        walk_tokens::<Generator, _>(
+            &self.name,
+            &self.generics,
+            &self.attrs,
+            &self.generic,
+            fields,
+            None,
+        )
+    }
+
+    fn describe_tokens(&self) -> syn::Result<TokenStream> {
+        let fields: &[NamedField] = &[];
+        walk_tokens::<Describer, _>(
+            &self.name,
+            &self.generics,
+            &self.attrs,
+            &self.generic,
+            fields,
+            None,
+        )
+    }
+}
+
 impl Unit {
     pub fn new(
         name: syn::Ident,
         generics: syn::Generics,
         attrs: Vec<syn::Attribute>,
     ) -> syn::Result<Self> {
-        let args = generics.type_params().cloned().map(|tp| tp.ident).collect();
         let attrs = GlobalAttr::parse_attrs(attrs)?;
+        attrs.check_struct()?;
         let generic = parse_or(&attrs.parse_type);
 
         Ok(Unit {
             attrs,
             name,
-            args,
+            generics,
             generic,
         })
     }
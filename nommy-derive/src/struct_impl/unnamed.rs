@@ -3,16 +3,21 @@ use std::convert::TryInto;
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
+use super::walk_tokens;
 use crate::{
     attr::GlobalAttr,
-    fn_impl::{parse_or, BuildOutput, FnImpl},
-    parsers::{FieldType, UnnamedField},
+    describe_impl::Describer,
+    fn_impl::{impl_line, in_context, memoize, parse_or, BuildOutput, FnImpl},
+    generate_impl::Generator,
+    parsers::{check_bits, FieldType, UnnamedField},
+    print_impl::Printer,
+    Item,
 };
 
 pub struct Unnamed {
     name: syn::Ident,
     fields: Vec<UnnamedField>,
-    args: Vec<syn::Ident>,
+    generics: syn::Generics,
     attrs: GlobalAttr,
     generic: syn::Type,
 }
@@ -21,7 +26,7 @@ impl ToTokens for Unnamed {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Unnamed {
             name,
-            args,
+            generics,
             generic,
             fields,
             attrs,
@@ -38,42 +43,86 @@ impl ToTokens for Unnamed {
         let BuildOutput {
             peek_impl,
             parse_impl,
-            wc,
+            mut wc,
         } = fn_impl.build(&name);
 
-        let impl_line = match attrs.parse_type {
-            Some(_) => quote!{
-                impl<#(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*>
+        let names = self.fields.iter().enumerate().map(|(i, f)| f.name(i));
+
+        let (parse_impl, peek_impl) = in_context(
+            quote! {
+                use ::nommy::eyre::WrapErr;
+                use ::std::convert::TryInto;
+                #parse_impl
+
+                Ok(#name (#(
+                    #names,
+                )*))
             },
-            None => quote!{
-                impl<#generic, #(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*> where #wc
+            quote! {
+                #peek_impl
+                true
             },
-        };
+        );
+        let (parse_body, peek_body) = memoize(attrs, &mut wc, parse_impl, peek_impl);
 
-        let names = self.fields.iter().enumerate().map(|(i, f)| f.name(i));
+        let impl_line = impl_line(name, generics, &attrs.parse_type, generic, &wc);
 
         tokens.extend(quote!{
             #[automatically_derived]
             #impl_line {
                 fn parse(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> {
-                    use ::nommy::eyre::WrapErr;
-                    use ::std::convert::TryInto;
-                    #parse_impl
-
-                    Ok(#name (#(
-                        #names,
-                    )*))
+                    #parse_body
                 }
 
                 fn peek(input: &mut impl ::nommy::Buffer<#generic>) -> bool {
-                    #peek_impl
-                    true
+                    #peek_body
                 }
             }
         })
     }
 }
 
+impl Item for Unnamed {
+    fn parse_tokens(&self) -> TokenStream {
+        self.to_token_stream()
+    }
+
+    fn print_tokens(&self) -> syn::Result<TokenStream> {
+        let names = self.fields.iter().enumerate().map(|(i, f)| f.name(i));
+        let pattern = quote! { Self(#(#names,)*) };
+        walk_tokens::<Printer, _>(
+            &self.name,
+            &self.generics,
+            &self.attrs,
+            &self.generic,
+            &self.fields,
+            Some(pattern),
+        )
+    }
+
+    fn generate_tokens(&self) -> syn::Result<TokenStream> {
+        walk_tokens::<Generator, _>(
+            &self.name,
+            &self.generics,
+            &self.attrs,
+            &self.generic,
+            &self.fields,
+            None,
+        )
+    }
+
+    fn describe_tokens(&self) -> syn::Result<TokenStream> {
+        walk_tokens::<Describer, _>(
+            &self.name,
+            &self.generics,
+            &self.attrs,
+            &self.generic,
+            &self.fields,
+            None,
+        )
+    }
+}
+
 impl Unnamed {
     pub fn new(
         name: syn::Ident,
@@ -81,19 +130,20 @@ impl Unnamed {
         attrs: Vec<syn::Attribute>,
         fields: syn::FieldsUnnamed,
     ) -> syn::Result<Self> {
-        let args = generics.type_params().cloned().map(|tp| tp.ident).collect();
-        let fields = fields
+        let fields: Vec<UnnamedField> = fields
             .unnamed
             .into_iter()
             .map(|f| f.try_into())
             .collect::<syn::Result<_>>()?;
         let attrs = GlobalAttr::parse_attrs(attrs)?;
+        attrs.check_struct()?;
+        check_bits(&attrs.parse_type, &fields)?;
         let generic = parse_or(&attrs.parse_type);
 
         Ok(Unnamed {
             attrs,
             name,
-            args,
+            generics,
             fields,
             generic,
         })
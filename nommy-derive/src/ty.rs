@@ -34,3 +34,31 @@ pub fn contains(ty: &syn::Type, type_name: &syn::Ident) -> bool {
         _ => true,
     }
 }
+
+/// Whether `ty` is a `Vec` or `Vec1`, which a type can nest inside of
+pub fn is_vec(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Group(ty) => is_vec(&ty.elem),
+        syn::Type::Paren(ty) => is_vec(&ty.elem),
+        syn::Type::Path(path) => matches!(
+            path.path.segments.last(),
+            Some(segment) if segment.ident == "Vec" || segment.ident == "Vec1"
+        ),
+        _ => false,
+    }
+}
+
+/// Gets `T` out of `Vec<T>`, or any other type with a single generic type argument
+pub fn element_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(ty) => ty.path.segments.last()?,
+        _ => return None,
+    };
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
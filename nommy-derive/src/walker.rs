@@ -0,0 +1,235 @@
+use std::marker::PhantomData;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::{
+    attr::{GlobalAttr, VecFieldAttr},
+    fn_impl::trait_impl_line,
+    parsers::FieldType,
+};
+
+/// What one of the derives that follow the parser writes out for each part of the input.
+/// [`Walker`] goes over the parts in the same order that [`Builder`](crate::fn_impl::Builder) parses them
+pub trait Backend {
+    /// The trait being derived, such as `::nommy::Print`
+    fn trait_path() -> TokenStream;
+
+    /// The bounds needed to write out a prefix, suffix, seperator or ignore type, which have no value to write
+    fn fix_bound(generic: &syn::Type, ty: &syn::Type) -> TokenStream {
+        Self::bound(generic, ty)
+    }
+
+    /// The bounds needed to write out a field of type `ty`
+    fn bound(generic: &syn::Type, ty: &syn::Type) -> TokenStream {
+        let trait_path = Self::trait_path();
+        quote! { #ty: #trait_path<#generic>, }
+    }
+
+    /// Writes out a prefix, suffix, seperator or ignore type
+    fn fix(generic: &syn::Type, ty: &syn::Type) -> TokenStream;
+
+    /// The bounds needed to write out a field of type `ty`, parsed by `parser` if it has one
+    fn field_bound(generic: &syn::Type, ty: &syn::Type, parser: Option<&syn::Type>) -> TokenStream {
+        Self::bound(generic, parser.unwrap_or(ty))
+    }
+
+    /// Writes out the field `name` of type `ty`, parsed by `parser` if it has one
+    fn value(
+        generic: &syn::Type,
+        ty: &syn::Type,
+        parser: Option<&syn::Type>,
+        _name: &syn::Ident,
+    ) -> TokenStream {
+        Self::fix(generic, parser.unwrap_or(ty))
+    }
+
+    /// Writes out a `bits = N` field. `key` is the `bits` parameter, for errors
+    fn bits(key: &proc_macro2::Ident, bits: &syn::Expr) -> syn::Result<TokenStream>;
+
+    /// Writes out the vec field `name`, with `elem` for each element and `between` in between them
+    fn vec(
+        name: &syn::Ident,
+        elem: TokenStream,
+        between: TokenStream,
+        attrs: &VecFieldAttr,
+    ) -> TokenStream;
+
+    /// The ignore types that are written out
+    fn ignored(ignore: &[syn::Type]) -> &[syn::Type] {
+        &ignore[..ignore.len().min(1)]
+    }
+
+    /// Writes out the ignored types, wherever the parser would skip over them
+    fn ignore(generic: &syn::Type, ignored: &[syn::Type]) -> TokenStream {
+        Self::fix(generic, &ignored[0])
+    }
+
+    /// Joins the parts that were written out, in order
+    fn join(parts: Vec<TokenStream>) -> TokenStream;
+
+    /// The trait's method. `pattern` destructures `self` into the field names, if it has fields
+    fn method(generic: &syn::Type, pattern: Option<TokenStream>, body: TokenStream) -> TokenStream;
+}
+
+/// Builds the body of a [`Backend`]'s method, writing out the parts of the input in the order that they're parsed
+pub struct Walker<'a, B> {
+    generic: &'a syn::Type,
+    type_name: &'a syn::Ident,
+
+    parts: Vec<TokenStream>,
+    wc: TokenStream,
+    after_each: Option<TokenStream>,
+    backend: PhantomData<B>,
+}
+
+impl<'a, B: Backend> Walker<'a, B> {
+    pub fn new(generic: &'a syn::Type, type_name: &'a syn::Ident) -> Self {
+        Self {
+            generic,
+            type_name,
+            parts: Vec::new(),
+            wc: TokenStream::new(),
+            after_each: None,
+            backend: PhantomData,
+        }
+    }
+
+    /// Walks over the prefix, fields and suffix of a struct or variant
+    pub fn fields<F: FieldType>(
+        generic: &'a syn::Type,
+        type_name: &'a syn::Ident,
+        attrs: &GlobalAttr,
+        fields: &[F],
+    ) -> syn::Result<(TokenStream, TokenStream)> {
+        let mut walker = Self::new(generic, type_name);
+
+        walker.create_ignore(&attrs.ignore);
+        walker.add_fix(&attrs.prefix);
+
+        for (field_num, field) in fields.iter().enumerate() {
+            walker.add_field(field, field_num)?;
+        }
+
+        walker.add_fix(&attrs.suffix);
+
+        Ok(walker.build())
+    }
+
+    /// Returns the body, and the where clause
+    pub fn build(self) -> (TokenStream, TokenStream) {
+        (B::join(self.parts), self.wc)
+    }
+
+    pub fn create_ignore(&mut self, ignore: &[syn::Type]) {
+        let ignored = B::ignored(ignore);
+        if ignored.is_empty() {
+            return;
+        }
+        for ty in ignored {
+            self.add_fix_where(ty);
+        }
+        self.after_each = Some(B::ignore(self.generic, ignored));
+    }
+
+    pub fn ignore(&mut self) {
+        if let Some(after_each) = &self.after_each {
+            self.parts.push(after_each.clone());
+        }
+    }
+
+    fn add_field_where(&mut self, ty: &syn::Type, parser: Option<&syn::Type>) {
+        if !crate::ty::contains(parser.unwrap_or(ty), self.type_name) {
+            self.wc.extend(B::field_bound(self.generic, ty, parser));
+        }
+    }
+
+    fn add_fix_where(&mut self, ty: &syn::Type) {
+        if !crate::ty::contains(ty, self.type_name) {
+            self.wc.extend(B::fix_bound(self.generic, ty));
+        }
+    }
+
+    pub fn add_raw(&mut self, tokens: TokenStream) {
+        self.parts.push(tokens);
+    }
+    pub fn add_where_raw(&mut self, tokens: TokenStream) {
+        self.wc.extend(tokens);
+    }
+
+    pub fn add_fix(&mut self, fix: &Option<syn::Type>) {
+        if let Some(fix) = fix {
+            self.add_fix_where(fix);
+            self.parts.push(B::fix(self.generic, fix));
+            self.ignore();
+        }
+    }
+
+    pub fn add_field<F: FieldType>(&mut self, field: &F, field_num: usize) -> syn::Result<()> {
+        let ty = field.ty();
+        let name = field.name(field_num);
+        let attrs = field.attrs();
+        let generic = self.generic;
+
+        if attrs.skip || attrs.default.is_some() {
+            return Ok(());
+        }
+        if let Some(bits) = &attrs.bits {
+            let key = attrs.keys.iter().find(|k| *k == "bits").unwrap();
+            self.parts.push(B::bits(key, bits)?);
+            return Ok(());
+        }
+
+        self.add_fix(&attrs.prefix);
+
+        if let Some(parser) = &attrs.vec.parser {
+            let elem_ty = crate::ty::element_type(ty).unwrap_or(parser);
+            self.add_field_where(elem_ty, Some(parser));
+            let elem = B::value(generic, elem_ty, Some(parser), &format_ident!("elem"));
+
+            let mut between: Vec<TokenStream> = self.after_each.iter().cloned().collect();
+            if let Some(sep) = &attrs.vec.seperated_by {
+                self.add_fix_where(sep);
+                between.push(B::fix(generic, sep));
+                between.extend(self.after_each.iter().cloned());
+            }
+
+            self.parts
+                .push(B::vec(&name, elem, B::join(between), &attrs.vec));
+        } else {
+            let parser: Option<&syn::Type> = (&attrs.parser).into();
+            self.add_field_where(ty, parser);
+            self.parts.push(B::value(generic, ty, parser, &name));
+            self.ignore();
+        }
+
+        self.add_fix(&attrs.suffix);
+        Ok(())
+    }
+}
+
+/// Implements the trait of `B` for the type `name`, with the `method` built by a [`Walker`]
+pub fn trait_impl<B: Backend>(
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    attrs: &GlobalAttr,
+    generic: &syn::Type,
+    wc: &TokenStream,
+    method: TokenStream,
+) -> TokenStream {
+    let impl_line = trait_impl_line(
+        B::trait_path(),
+        name,
+        generics,
+        &attrs.parse_type,
+        generic,
+        wc,
+    );
+
+    quote! {
+        #[automatically_derived]
+        #impl_line {
+            #method
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use nommy::{bytes::*, parse_terminated, print, Parse, Print};
+
+#[derive(Debug, Parse, Print, PartialEq)]
+#[nommy(parse_type = u8)]
+#[nommy(prefix = Tag<b"\x89PNG">)]
+struct Header {
+    #[nommy(parser = BigEndian<u32>)]
+    length: u32,
+
+    #[nommy(parser = LittleEndian<i16>)]
+    offset: i16,
+
+    #[nommy(parser = BigEndian<f32>)]
+    scale: f32,
+}
+
+#[derive(Debug, Parse, Print, PartialEq)]
+#[nommy(parse_type = u8)]
+struct Entry {
+    #[nommy(parser = NulTerminated)]
+    name: String,
+
+    #[nommy(parser = LengthPrefixed<LittleEndian<u16>>)]
+    data: Vec<u8>,
+
+    #[nommy(parser = Padded<8, b' '>)]
+    kind: String,
+}
+
+fn main() {
+    let mut input = b"\x89PNG".to_vec();
+    input.extend_from_slice(&13_u32.to_be_bytes());
+    input.extend_from_slice(&(-2_i16).to_le_bytes());
+    input.extend_from_slice(&0.5_f32.to_be_bytes());
+
+    let header: Header = parse_terminated(input.clone()).unwrap();
+    assert_eq!(
+        header,
+        Header {
+            length: 13,
+            offset: -2,
+            scale: 0.5,
+        }
+    );
+    // fields are printed by their parser, not as the field type
+    let output: Vec<u8> = print(&header);
+    assert_eq!(output, input);
+
+    let mut input = b"foo.txt\0".to_vec();
+    input.extend_from_slice(&[3, 0, 1, 2, 3]);
+    input.extend_from_slice(b"text    ");
+
+    let entry: Entry = parse_terminated(input.clone()).unwrap();
+    assert_eq!(
+        entry,
+        Entry {
+            name: "foo.txt".to_string(),
+            data: vec![1, 2, 3],
+            kind: "text".to_string(),
+        }
+    );
+    let output: Vec<u8> = print(&entry);
+    assert_eq!(output, input);
+
+    let err = parse_terminated::<Entry, _>(b"\xff\0\0\0        ".to_vec()).unwrap_err();
+    assert!(err.downcast_ref::<std::string::FromUtf8Error>().is_some());
+}
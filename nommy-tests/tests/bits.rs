@@ -0,0 +1,63 @@
+use nommy::{bytes::*, parse_terminated, Parse};
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(parse_type = u8)]
+struct Ipv4Header {
+    #[nommy(bits = 4)]
+    version: u8,
+    #[nommy(bits = 4)]
+    ihl: u8,
+    #[nommy(bits = 6)]
+    dscp: u8,
+    #[nommy(bits = 2)]
+    ecn: u8,
+
+    #[nommy(parser = BigEndian<u16>)]
+    total_length: u16,
+    #[nommy(parser = BigEndian<u16>)]
+    identification: u16,
+
+    #[nommy(bits = 1)]
+    reserved: bool,
+    #[nommy(bits = 1)]
+    dont_fragment: bool,
+    #[nommy(bits = 1)]
+    more_fragments: bool,
+    #[nommy(bits = 13)]
+    fragment_offset: u16,
+}
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(parse_type = u8)]
+struct Misaligned {
+    #[nommy(bits = 3)]
+    a: u8,
+    #[nommy(parser = BigEndian<u16>)]
+    b: u16,
+}
+
+fn main() {
+    let input = vec![0x45, 0x02, 0x00, 0x54, 0x12, 0x34, 0x40, 0x05];
+    let header: Ipv4Header = parse_terminated(input).unwrap();
+    assert_eq!(
+        header,
+        Ipv4Header {
+            version: 4,
+            ihl: 5,
+            dscp: 0,
+            ecn: 2,
+            total_length: 0x54,
+            identification: 0x1234,
+            reserved: false,
+            dont_fragment: true,
+            more_fragments: false,
+            fragment_offset: 5,
+        }
+    );
+
+    let err = parse_terminated::<Misaligned, _>(vec![0xff, 0x00, 0x01]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "bit fields of `Misaligned` do not end on a byte boundary"
+    );
+}
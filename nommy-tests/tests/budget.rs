@@ -0,0 +1,59 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use nommy::{
+    context::{Context, Interrupted},
+    text::*,
+    Buf, Buffer, Parse,
+};
+
+/// Every level is parsed twice when the closing tag is wrong, so this backtracks exponentially
+#[derive(Debug, Parse)]
+#[nommy(parse_type = char)]
+enum Slow {
+    #[nommy(prefix = Tag<"a">, suffix = Tag<"b">)]
+    B(Box<Slow>),
+    #[nommy(prefix = Tag<"a">, suffix = Tag<"c">)]
+    C(Box<Slow>),
+    #[nommy(prefix = Tag<"x">)]
+    X,
+}
+
+fn slow_input(depth: usize) -> String {
+    "a".repeat(depth) + "x" + &"c".repeat(depth)
+}
+
+fn main() {
+    let input = slow_input(4);
+    let mut buffer = Buf::new(input.chars()).with_context(Context::new().budget(10_000));
+    Slow::parse(&mut buffer).unwrap();
+
+    let input = slow_input(40);
+    let mut buffer = Buf::new(input.chars()).with_context(Context::new().budget(10_000));
+    let err = Slow::parse(&mut buffer).unwrap_err();
+    assert_eq!(
+        err.downcast_ref(),
+        Some(&Interrupted::BudgetExhausted { budget: 10_000 })
+    );
+    assert_eq!(buffer.context().unwrap().steps(), 10_000);
+
+    let mut buffer = Buf::new(input.chars()).with_context(Context::new().budget(10_000));
+    assert!(!Slow::peek(&mut buffer));
+
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let context = Context::new().cancel_flag(Arc::clone(&cancel_flag));
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancel_flag.store(true, Ordering::Relaxed);
+    });
+    let mut buffer = Buf::new(input.chars()).with_context(context);
+    let err = Slow::parse(&mut buffer).unwrap_err();
+    assert_eq!(err.downcast_ref(), Some(&Interrupted::Cancelled));
+    canceller.join().unwrap();
+}
@@ -0,0 +1,137 @@
+use nommy::{
+    context::{Context, DepthExceeded, DEFAULT_MAX_DEPTH},
+    parse, parse_terminated,
+    text::*,
+    Buf, Parse,
+};
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+enum JSON {
+    #[nommy(prefix = Tag<"null">)]
+    Null,
+
+    #[nommy(prefix = Tag<"{">, suffix = Tag<"}">)]
+    Object(
+        #[nommy(inner_parser = Record)]
+        #[nommy(seperated_by = Tag<",">)]
+        Vec<Record>,
+    ),
+
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    List(
+        #[nommy(inner_parser = JSON)]
+        #[nommy(seperated_by = Tag<",">)]
+        Vec<JSON>,
+    ),
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+struct Record {
+    #[nommy(prefix = Tag<"\"">, suffix = Tag<"\":">)]
+    #[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)]
+    name: String,
+
+    value: JSON,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(parse_type = char)]
+enum Expr {
+    #[nommy(infix = Tag<"+">, precedence = 1)]
+    Add(Box<Expr>, Box<Expr>),
+    #[nommy(prefix = Tag<"-">, precedence = 2)]
+    Neg(Box<Expr>),
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    Paren(Box<Expr>),
+    Num(#[nommy(parser = AnyOf1<"0123456789">)] String),
+}
+
+/// `Outer` only reaches itself through `Inner`
+#[derive(Debug, Parse)]
+#[nommy(parse_type = char)]
+enum Outer {
+    List(#[nommy(prefix = Tag<"[">)] Vec<Inner>),
+}
+
+#[derive(Debug, Parse)]
+#[nommy(parse_type = char)]
+struct Inner {
+    outer: Outer,
+}
+
+/// Wrappers that can't reach themselves don't count towards the depth
+#[derive(Debug, Parse)]
+#[nommy(parse_type = char)]
+struct Wrapper(Middle);
+
+#[derive(Debug, Parse)]
+#[nommy(parse_type = char)]
+struct Middle(#[nommy(prefix = Tag<"(">, suffix = Tag<")">)] Leaf);
+
+#[derive(Debug, Parse)]
+#[nommy(parse_type = char)]
+struct Leaf(#[nommy(parser = AnyOf1<"0123456789">)] String);
+
+fn nested(open: &str, inner: &str, close: &str, depth: usize) -> String {
+    open.repeat(depth) + inner + &close.repeat(depth)
+}
+
+fn depth_exceeded<P: std::fmt::Debug>(res: nommy::eyre::Result<P>, max_depth: usize) {
+    let err = res.unwrap_err();
+    assert_eq!(err.downcast_ref(), Some(&DepthExceeded { max_depth }));
+}
+
+fn main() {
+    // adversarial input fails instead of overflowing the stack
+    let input = "[".repeat(10_000);
+    depth_exceeded(parse::<JSON, _>(input.chars()), DEFAULT_MAX_DEPTH);
+    assert!(!JSON::peek(&mut Buf::new(input.chars())));
+
+    let input = r#"{"a":"#.repeat(10_000);
+    depth_exceeded(parse::<JSON, _>(input.chars()), DEFAULT_MAX_DEPTH);
+
+    let input = "-".repeat(10_000) + "1";
+    depth_exceeded(parse::<Expr, _>(input.chars()), DEFAULT_MAX_DEPTH);
+    let input = "(".repeat(10_000);
+    depth_exceeded(parse::<Expr, _>(input.chars()), DEFAULT_MAX_DEPTH);
+    let input = "[".repeat(10_000);
+    depth_exceeded(parse::<Outer, _>(input.chars()), DEFAULT_MAX_DEPTH);
+    assert!(!Outer::peek(&mut Buf::new(input.chars())));
+
+    // reasonable nesting still parses
+    let input = nested("[", "null", "]", 50);
+    parse_terminated::<JSON, _>(input.chars()).unwrap();
+    let input = nested("(", "1+2", ")", 20);
+    parse_terminated::<Expr, _>(input.chars()).unwrap();
+    parse_terminated::<Outer, _>("[[[".chars()).unwrap();
+
+    // objects only count once per level, even though they nest through `Record`
+    let input = nested(r#"{"a":"#, "null", "}", 100);
+    parse_terminated::<JSON, _>(input.chars()).unwrap();
+    let input = nested("[", "null", "]", DEFAULT_MAX_DEPTH);
+    parse_terminated::<JSON, _>(input.chars()).unwrap();
+    let input = nested("[", "null", "]", DEFAULT_MAX_DEPTH + 1);
+    depth_exceeded(
+        parse_terminated::<JSON, _>(input.chars()),
+        DEFAULT_MAX_DEPTH,
+    );
+
+    let mut buffer = Buf::new("(123)".chars()).with_context(Context::new().max_depth(0));
+    Wrapper::parse(&mut buffer).unwrap();
+
+    // the limit can be changed
+    let input = nested("[", "null", "]", 4);
+    let mut buffer = Buf::new(input.chars()).with_context(Context::new().max_depth(4));
+    JSON::parse(&mut buffer).unwrap();
+    let input = nested("[", "null", "]", 5);
+    let mut buffer = Buf::new(input.chars()).with_context(Context::new().max_depth(4));
+    depth_exceeded(JSON::parse(&mut buffer), 4);
+
+    let input = nested("[", "null", "]", 200);
+    let mut buffer = Buf::new(input.chars()).with_context(Context::new().max_depth(1000));
+    JSON::parse(&mut buffer).unwrap();
+}
@@ -0,0 +1,130 @@
+use nommy::{generate, parse_terminated, text::*, Buffer, Generate, Generator, Parse};
+
+type Ident = WhileNot1<" \t\r\n()">;
+type Integer = AnyOf1<"0123456789">;
+
+#[derive(Debug, PartialEq, Parse, Generate)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+enum Exp {
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    SExp(Vec<Exp>),
+    Number(#[nommy(parser = Number)] String),
+    Symbol(#[nommy(parser = Ident)] String),
+}
+
+#[derive(Debug, PartialEq, Parse, Generate)]
+struct Number {
+    #[nommy(parser = Integer)]
+    integer: String,
+    decimal: Option<Decimal>,
+}
+
+#[derive(Debug, PartialEq, Parse, Generate)]
+#[nommy(prefix = Tag<".">)]
+struct Decimal(#[nommy(parser = Integer)] String);
+
+impl Into<String> for Number {
+    fn into(self) -> String {
+        match self.decimal {
+            Some(d) => format!("{}.{}", self.integer, d.0),
+            None => self.integer,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Parse, Generate)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+enum JSON {
+    #[nommy(prefix = Tag<"null">)]
+    Null,
+
+    #[nommy(prefix = Tag<"{">, suffix = Tag<"}">)]
+    Object(
+        #[nommy(inner_parser = Record)]
+        #[nommy(seperated_by = Tag<",">)]
+        Vec<Record>,
+    ),
+
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    List(
+        #[nommy(inner_parser = JSON)]
+        #[nommy(seperated_by = Tag<",">, trailing = "maybe")]
+        Vec<JSON>,
+    ),
+
+    String(#[nommy(parser = StringParser)] String),
+}
+
+#[derive(Debug, PartialEq, Parse, Generate)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+struct Record {
+    #[nommy(parser = StringParser)]
+    #[nommy(suffix = Tag<":">)]
+    name: String,
+
+    value: JSON,
+}
+
+struct StringParser(String);
+impl Parse<char> for StringParser {
+    fn parse(input: &mut impl Buffer<char>) -> nommy::eyre::Result<Self> {
+        if input.next() != Some('"') {
+            return Err(nommy::eyre::eyre!("starting quote not found"));
+        }
+        let mut output = String::new();
+        loop {
+            match input.next() {
+                Some('"') => return Ok(StringParser(output)),
+                Some('\\') => output.extend(input.next()),
+                Some(c) => output.push(c),
+                None => return Err(nommy::eyre::eyre!("ending quote not found")),
+            }
+        }
+    }
+}
+impl Generate<char> for StringParser {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<char>) {
+        output.extend(Some('"'));
+        for _ in 0..gen.repeat() {
+            match *gen.rng().choose(&['a', 'b', '"', '\\']) {
+                c @ '"' | c @ '\\' => output.extend(vec!['\\', c]),
+                c => output.extend(Some(c)),
+            }
+        }
+        output.extend(Some('"'));
+    }
+}
+impl Into<String> for StringParser {
+    fn into(self) -> String {
+        self.0
+    }
+}
+
+fn main() {
+    let mut gen = Generator::new(1234).max_depth(5);
+    for _ in 0..200 {
+        let input: String = generate::<Exp, _, _>(&mut gen);
+        if let Err(err) = parse_terminated::<Exp, _>(input.chars()) {
+            panic!("{:?} was not accepted: {:?}", input, err);
+        }
+        let input: String = generate::<JSON, _, _>(&mut gen);
+        if let Err(err) = parse_terminated::<JSON, _>(input.chars()) {
+            panic!("{:?} was not accepted: {:?}", input, err);
+        }
+    }
+
+    // the same seed generates the same input
+    let a: String = generate::<JSON, _, _>(&mut Generator::new(1));
+    let b: String = generate::<JSON, _, _>(&mut Generator::new(1));
+    assert_eq!(a, b);
+
+    // once the depth runs out, only the variants that can end are picked
+    let mut gen = Generator::new(0).max_depth(1);
+    for _ in 0..20 {
+        let input: String = generate::<Exp, _, _>(&mut gen);
+        assert!(!input.contains('('), "{:?}", input);
+    }
+}
@@ -0,0 +1,59 @@
+use std::{fmt::Debug, marker::PhantomData};
+
+use nommy::{parse_terminated, text::*, Parse};
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(ignore = WhiteSpace)]
+struct Node<'a, T: Clone, const N: usize>
+where
+    T: Debug,
+{
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    items: [T; N],
+
+    #[nommy(skip)]
+    source: PhantomData<&'a str>,
+}
+
+#[derive(Debug, Parse, PartialEq)]
+struct Pair<'a, T: Clone>(T, T, #[nommy(skip)] PhantomData<&'a ()>)
+where
+    T: Debug;
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(parse_type = char)]
+struct Wrapped<T>(#[nommy(skip)] PhantomData<T>, Tag<".">)
+where
+    T: Default;
+
+#[derive(Debug, Parse, PartialEq)]
+enum Either<'a, L: Clone, R, const N: usize>
+where
+    R: Debug,
+{
+    Left([L; N]),
+    Right(R, #[nommy(skip)] PhantomData<&'a ()>),
+}
+
+fn main() {
+    let node: Node<Tag<".">, 3> = parse_terminated("[ ... ]".chars()).unwrap();
+    assert_eq!(
+        node,
+        Node {
+            items: [Tag::<".">; 3],
+            source: PhantomData,
+        }
+    );
+
+    let pair: Pair<Tag<"-">> = parse_terminated("--".chars()).unwrap();
+    assert_eq!(pair, Pair(Tag::<"-">, Tag::<"-">, PhantomData));
+
+    let wrapped: Wrapped<u8> = parse_terminated(".".chars()).unwrap();
+    assert_eq!(wrapped, Wrapped(PhantomData, Tag::<".">));
+
+    let either: Either<Tag<"<">, Tag<">">, 2> = parse_terminated("<<".chars()).unwrap();
+    assert_eq!(either, Either::Left([Tag::<"<">; 2]));
+
+    let either: Either<Tag<"<">, Tag<">">, 2> = parse_terminated(">".chars()).unwrap();
+    assert_eq!(either, Either::Right(Tag::<">">, PhantomData));
+}
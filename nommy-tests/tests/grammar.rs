@@ -0,0 +1,106 @@
+use nommy::{
+    bytes::{self, BigEndian},
+    grammar::Grammar,
+    text::*,
+    Describe, Parse,
+};
+
+#[derive(Debug, Parse, Describe)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+enum JSON {
+    #[nommy(prefix = Tag<"null">)]
+    Null,
+
+    #[nommy(prefix = Tag<"{">, suffix = Tag<"}">)]
+    Object(
+        #[nommy(inner_parser = Record)]
+        #[nommy(seperated_by = Tag<",">)]
+        Vec<Record>,
+    ),
+
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    List(
+        #[nommy(inner_parser = JSON)]
+        #[nommy(seperated_by = Tag<",">, trailing = "maybe")]
+        Vec<JSON>,
+    ),
+
+    String(#[nommy(parser = StringParser)] String),
+}
+
+#[derive(Debug, Parse, Describe)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+struct Record {
+    #[nommy(parser = StringParser)]
+    #[nommy(suffix = Tag<":">)]
+    name: String,
+
+    value: JSON,
+}
+
+#[derive(Debug, Parse, Describe)]
+#[nommy(prefix = Tag<"\"">, suffix = Tag<"\"">)]
+struct StringParser(#[nommy(parser = WhileNot1<"\"">)] String);
+
+impl Into<String> for StringParser {
+    fn into(self) -> String {
+        self.0
+    }
+}
+
+#[derive(Debug, Parse, Describe)]
+#[nommy(parse_type = u8)]
+#[nommy(prefix = bytes::Tag<b"HDR\0">)]
+struct Header {
+    #[nommy(bits = 4)]
+    version: u8,
+    #[nommy(bits = 4)]
+    flags: u8,
+    length: BigEndian<u16>,
+}
+
+#[derive(Debug, Parse, Describe)]
+#[nommy(parse_type = char)]
+struct Pair<T: Parse<char> + Describe<char>>(T, #[nommy(prefix = Tag<",">)] T);
+
+#[derive(Debug, Parse, Describe)]
+#[nommy(parse_type = char)]
+struct Pairs {
+    digits: Pair<AnyOf1<"0123456789">>,
+    #[nommy(prefix = Tag<";">)]
+    letters: Pair<AnyOf1<"ab">>,
+}
+
+fn main() {
+    let grammar = Grammar::of::<JSON, char>();
+    assert_eq!(
+        grammar.to_ebnf(),
+        r#"JSON ::= "null" WhiteSpace* | "{" WhiteSpace* (Record (WhiteSpace* "," WhiteSpace* Record)*)? "}" WhiteSpace* | "[" WhiteSpace* (JSON (WhiteSpace* "," WhiteSpace* JSON)*)? (WhiteSpace* "," WhiteSpace*)? "]" WhiteSpace* | StringParser WhiteSpace*
+WhiteSpace ::= [#x20#x9#xA] | #xD #xA
+Record ::= StringParser WhiteSpace* ":" WhiteSpace* JSON WhiteSpace*
+StringParser ::= '"' [^"]+ '"'
+"#
+    );
+
+    let svg = grammar.to_svg();
+    for name in &["JSON", "Record", "StringParser", "WhiteSpace"] {
+        assert!(svg.contains(&format!(">{}</text>", name)));
+    }
+
+    let grammar = Grammar::of::<Header, u8>();
+    assert_eq!(
+        grammar.to_ebnf(),
+        "Header ::= \"HDR\" #x0 /* 4 bits */ /* 4 bits */ /* BigEndian<u16> */\n"
+    );
+
+    let grammar = Grammar::of::<Pairs, char>();
+    assert_eq!(
+        grammar.to_ebnf(),
+        r#"Pairs ::= Pair_AnyOf1_0123456789 ";" Pair_AnyOf1_ab
+Pair_AnyOf1_0123456789 ::= [0123456789]+ "," [0123456789]+
+Pair_AnyOf1_ab ::= [ab]+ "," [ab]+
+"#
+    );
+}
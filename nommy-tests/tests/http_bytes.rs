@@ -0,0 +1,75 @@
+use nommy::{
+    bytes::{AnyOf1, Space, Tag, WhileNot1},
+    parse, Parse,
+};
+
+type Letters = AnyOf1<b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ-_">;
+type Path = AnyOf1<b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ/%-_+1234567890">;
+type Digits = AnyOf1<b"0123456789">;
+
+#[derive(Debug, Parse)]
+#[nommy(parse_type = u8)]
+struct HTTP {
+    #[nommy(parser = Letters)]
+    method: String,
+
+    #[nommy(parser = Path)]
+    #[nommy(prefix = Tag<b" ">, suffix = Tag<b" ">)]
+    path: String,
+
+    #[nommy(prefix = Tag<b"HTTP/">)]
+    #[nommy(parser = Digits)]
+    version_major: usize,
+    #[nommy(prefix = Tag<b".">)]
+    #[nommy(parser = Digits)]
+    version_minor: usize,
+
+    headers: Vec<Header>,
+}
+
+#[derive(Debug, Parse)]
+#[nommy(parse_type = u8)]
+#[nommy(ignore = Space)]
+#[nommy(prefix = Tag<b"\n">)]
+struct Header {
+    #[nommy(parser = Letters)]
+    name: String,
+
+    #[nommy(prefix = Tag<b":">)]
+    #[nommy(inner_parser = HeaderValue)]
+    values: Vec<String>,
+}
+
+#[derive(Debug, Parse)]
+#[nommy(parse_type = u8)]
+#[nommy(ignore = Space)]
+#[nommy(suffix = Option<Tag<b",">>)]
+struct HeaderValue {
+    #[nommy(parser = WhileNot1<b",\n">)]
+    value: String,
+}
+
+impl Into<String> for HeaderValue {
+    fn into(self) -> String {
+        self.value
+    }
+}
+
+fn main() {
+    let input = "GET / HTTP/1.1
+Host: www.reddit.com
+User-Agent: Mozilla/5.0 (Macintosh; Intel Mac OS X 10.8; rv:15.0) Gecko/20100101 Firefox/15.0.1
+Accept: text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8
+Accept-Language: en-us,en;q=0.5
+Accept-Encoding: gzip, deflate
+Connection: keep-alive
+
+";
+
+    let http: HTTP = parse(input.bytes()).unwrap();
+    assert_eq!(http.method, "GET");
+    assert_eq!(http.path, "/");
+    assert_eq!((http.version_major, http.version_minor), (1, 1));
+    assert_eq!(http.headers.len(), 6);
+    assert_eq!(http.headers[4].values, vec!["gzip", "deflate"]);
+}
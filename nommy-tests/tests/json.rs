@@ -107,4 +107,9 @@ fn main() {
 
     let json: JSON = parse(json_input.chars()).unwrap();
     println!("{:?}", json);
+
+    // peek has to agree with parse, including the seperators
+    let mut input = r#"["a", null, {"b": []}]."#.chars().into_buf();
+    assert!(JSON::peek(&mut input));
+    assert_eq!(input.next(), Some('.'));
 }
@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use nommy::{parse, parse_terminated, text::*, Buffer, IntoBuf, Parse};
+
+#[derive(Debug, Clone, PartialEq, Parse)]
+#[nommy(memoize, parse_type = char, ignore = WhiteSpace)]
+enum Expr {
+    Add(Box<Expr>, #[nommy(prefix = Tag<"+">)] Term),
+    Sub(Box<Expr>, #[nommy(prefix = Tag<"-">)] Term),
+    Term(Term),
+}
+
+#[derive(Debug, Clone, PartialEq, Parse)]
+#[nommy(memoize, parse_type = char, ignore = WhiteSpace)]
+enum Term {
+    Mul(Box<Term>, #[nommy(prefix = Tag<"*">)] Atom),
+    Atom(Atom),
+}
+
+#[derive(Debug, Clone, PartialEq, Parse)]
+#[nommy(parse_type = char, ignore = WhiteSpace)]
+enum Atom {
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    Paren(Box<Expr>),
+    Number(#[nommy(parser = AnyOf1<"0123456789">)] String),
+}
+
+/// How many `Chain`s have been cloned
+static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(memoize, parse_type = char)]
+enum Chain {
+    Add(
+        Box<Chain>,
+        #[nommy(prefix = Tag<"+">, parser = AnyOf1<"0123456789">)] String,
+    ),
+    Number(#[nommy(parser = AnyOf1<"0123456789">)] String),
+}
+
+impl Clone for Chain {
+    fn clone(&self) -> Self {
+        CLONES.fetch_add(1, Ordering::Relaxed);
+        match self {
+            Self::Add(lhs, rhs) => Self::Add(lhs.clone(), rhs.clone()),
+            Self::Number(n) => Self::Number(n.clone()),
+        }
+    }
+}
+
+impl Expr {
+    fn eval(&self) -> i64 {
+        match self {
+            Self::Add(lhs, rhs) => lhs.eval() + rhs.eval(),
+            Self::Sub(lhs, rhs) => lhs.eval() - rhs.eval(),
+            Self::Term(term) => term.eval(),
+        }
+    }
+}
+
+impl Term {
+    fn eval(&self) -> i64 {
+        match self {
+            Self::Mul(lhs, rhs) => lhs.eval() * rhs.eval(),
+            Self::Atom(atom) => atom.eval(),
+        }
+    }
+}
+
+impl Atom {
+    fn eval(&self) -> i64 {
+        match self {
+            Self::Paren(expr) => expr.eval(),
+            Self::Number(n) => n.parse().unwrap(),
+        }
+    }
+}
+
+fn number(n: &str) -> Term {
+    Term::Atom(Atom::Number(n.to_string()))
+}
+
+fn main() {
+    // left recursion makes the operators left associative
+    let expr: Expr = parse_terminated("1 - 2 - 3".chars()).unwrap();
+    assert_eq!(
+        expr,
+        Expr::Sub(
+            Box::new(Expr::Sub(Box::new(Expr::Term(number("1"))), number("2"))),
+            number("3")
+        )
+    );
+    assert_eq!(expr.eval(), -4);
+
+    let expr: Expr = parse_terminated("2 * 3 + 4 * 5 - (1 + 1) * 2".chars()).unwrap();
+    assert_eq!(expr.eval(), 22);
+
+    let mut input = "8 - 4 - 2)".chars().into_buf();
+    assert!(Expr::peek(&mut input.cursor()));
+    assert_eq!(Expr::parse(&mut input).unwrap().eval(), 2);
+    assert_eq!(input.next(), Some(')'));
+
+    let res: Result<Expr, _> = parse("+ 1".chars());
+    assert!(res.is_err());
+
+    // growing a long chain doesn't copy it at every step
+    let n = 2000;
+    let input = vec!["1"; n].join("+");
+    let mut chain: Chain = parse_terminated(input.chars()).unwrap();
+    assert!(CLONES.load(Ordering::Relaxed) <= 2 * n);
+    for _ in 1..n {
+        chain = match chain {
+            Chain::Add(lhs, rhs) if rhs == "1" => *lhs,
+            chain => panic!("expected `Add`, got {:?}", chain),
+        };
+    }
+    assert_eq!(chain, Chain::Number("1".to_string()));
+}
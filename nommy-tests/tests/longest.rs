@@ -0,0 +1,59 @@
+use nommy::{parse, parse_terminated, text::*, Buffer, IntoBuf, Parse};
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(longest_match)]
+enum Cmp {
+    Lt(Tag<"<">),
+    Le(Tag<"<=">),
+    Shl(Tag<"<<">),
+    #[nommy(prefix = Tag<"<">)]
+    AlsoLt,
+}
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(longest_match)]
+#[nommy(ignore = WhiteSpace)]
+enum Item {
+    Word(#[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)] String),
+    Call(
+        #[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)] String,
+        #[nommy(prefix = Tag<"(">, suffix = Tag<")">)] Vec<Item>,
+    ),
+}
+
+fn main() {
+    let cmp: Cmp = parse_terminated("<=".chars()).unwrap();
+    assert_eq!(cmp, Cmp::Le(Tag::<"<=">));
+
+    let cmp: Cmp = parse_terminated("<<".chars()).unwrap();
+    assert_eq!(cmp, Cmp::Shl(Tag::<"<<">));
+
+    // equal lengths are resolved in declaration order
+    let cmp: Cmp = parse_terminated("<".chars()).unwrap();
+    assert_eq!(cmp, Cmp::Lt(Tag::<"<">));
+
+    let res: Result<Cmp, _> = parse(">".chars());
+    assert_eq!(
+        format!("{}", res.unwrap_err()),
+        "no variants of Cmp could be parsed"
+    );
+
+    let mut input = "<=<<".chars().into_buf();
+    let mut cursor = input.cursor();
+    assert!(Cmp::peek(&mut cursor));
+    assert_eq!(cursor.next(), Some('<'));
+    assert_eq!(Cmp::parse(&mut input).unwrap(), Cmp::Le(Tag::<"<=">));
+    assert_eq!(Cmp::parse(&mut input).unwrap(), Cmp::Shl(Tag::<"<<">));
+
+    let item: Item = parse_terminated("foo(bar baz())".chars()).unwrap();
+    assert_eq!(
+        item,
+        Item::Call(
+            "foo".to_string(),
+            vec![
+                Item::Word("bar".to_string()),
+                Item::Call("baz".to_string(), vec![]),
+            ]
+        )
+    );
+}
@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use nommy::{eyre, parse_terminated, text::*, Buffer, IntoBuf, Parse};
+
+static DIGITS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts how many times it's parsed
+#[derive(Debug, Clone, PartialEq)]
+struct Digits(String);
+
+impl Parse<char> for Digits {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        DIGITS.fetch_add(1, Ordering::Relaxed);
+        let digits: String = AnyOf1::<"0123456789">::parse(input)?.into();
+        Ok(Self(digits))
+    }
+
+    fn peek(input: &mut impl Buffer<char>) -> bool {
+        DIGITS.fetch_add(1, Ordering::Relaxed);
+        AnyOf1::<"0123456789">::peek(input)
+    }
+}
+
+// every variant starts with a `Term`, so without memoization
+// each level of nesting parses the level inside it three times
+#[derive(Debug, Clone, PartialEq, Parse)]
+#[nommy(memoize, parse_type = char)]
+enum Expr {
+    Add(Term, #[nommy(prefix = Tag<"+">)] Box<Expr>),
+    Sub(Term, #[nommy(prefix = Tag<"-">)] Box<Expr>),
+    Term(Term),
+}
+
+#[derive(Debug, Clone, PartialEq, Parse)]
+#[nommy(memoize, parse_type = char)]
+enum Term {
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    Paren(Box<Expr>),
+    Number(Digits),
+}
+
+fn number(n: &str) -> Term {
+    Term::Number(Digits(n.to_string()))
+}
+
+fn main() {
+    let depth = 10;
+    let input = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+    let mut expected = Expr::Term(number("1"));
+    for _ in 0..depth {
+        expected = Expr::Term(Term::Paren(Box::new(expected)));
+    }
+
+    let expr: Expr = parse_terminated(input.chars()).unwrap();
+    assert_eq!(expr, expected);
+    assert_eq!(DIGITS.swap(0, Ordering::Relaxed), 1);
+
+    let mut input = "1+(2-3)".chars().into_buf();
+    assert!(Expr::peek(&mut input.cursor()));
+    let expr = Expr::parse(&mut input).unwrap();
+    assert_eq!(
+        expr,
+        Expr::Add(
+            number("1"),
+            Box::new(Expr::Term(Term::Paren(Box::new(Expr::Sub(
+                number("2"),
+                Box::new(Expr::Term(number("3")))
+            )))))
+        )
+    );
+    assert_eq!(input.next(), None);
+    // peeking and parsing each number once
+    assert_eq!(DIGITS.swap(0, Ordering::Relaxed), 3 + 3);
+}
@@ -0,0 +1,85 @@
+use nommy::{parse, parse_terminated, print, text::*, Buffer, IntoBuf, Parse, Print};
+
+#[derive(Debug, PartialEq, Parse, Print)]
+#[nommy(parse_type = char, ignore = WhiteSpace)]
+enum Expr {
+    #[nommy(infix = Tag<"+">, precedence = 10)]
+    Add(Box<Expr>, Box<Expr>),
+    #[nommy(infix = Tag<"-">, precedence = 10, assoc = "left")]
+    Sub(Box<Expr>, Box<Expr>),
+    #[nommy(infix = Tag<"*">, precedence = 20)]
+    Mul(Box<Expr>, Box<Expr>),
+    #[nommy(infix = Tag<"**">, precedence = 30, assoc = "right")]
+    Pow {
+        base: Box<Expr>,
+        exp: Box<Expr>,
+    },
+    #[nommy(prefix = Tag<"-">, precedence = 40)]
+    Neg(Box<Expr>),
+    #[nommy(postfix = Tag<"!">, precedence = 50)]
+    Fact(Box<Expr>),
+
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    Paren(Box<Expr>),
+    Num(#[nommy(parser = AnyOf1<"0123456789">)] String),
+}
+
+impl Expr {
+    fn eval(&self) -> i64 {
+        match self {
+            Self::Add(lhs, rhs) => lhs.eval() + rhs.eval(),
+            Self::Sub(lhs, rhs) => lhs.eval() - rhs.eval(),
+            Self::Mul(lhs, rhs) => lhs.eval() * rhs.eval(),
+            Self::Pow { base, exp } => base.eval().pow(exp.eval() as u32),
+            Self::Neg(expr) => -expr.eval(),
+            Self::Fact(expr) => (1..=expr.eval()).product(),
+            Self::Paren(expr) => expr.eval(),
+            Self::Num(n) => n.parse().unwrap(),
+        }
+    }
+}
+
+fn num(n: &str) -> Box<Expr> {
+    Box::new(Expr::Num(n.to_string()))
+}
+
+fn main() {
+    let expr: Expr = parse_terminated("1 - 2 - 3".chars()).unwrap();
+    assert_eq!(
+        expr,
+        Expr::Sub(Box::new(Expr::Sub(num("1"), num("2"))), num("3"))
+    );
+
+    let expr: Expr = parse_terminated("2 ** 3 ** 2".chars()).unwrap();
+    assert_eq!(
+        expr,
+        Expr::Pow {
+            base: num("2"),
+            exp: Box::new(Expr::Pow {
+                base: num("3"),
+                exp: num("2"),
+            }),
+        }
+    );
+    assert_eq!(expr.eval(), 512);
+
+    let expr: Expr = parse_terminated("1 + 2 * 3".chars()).unwrap();
+    assert_eq!(
+        expr,
+        Expr::Add(num("1"), Box::new(Expr::Mul(num("2"), num("3"))))
+    );
+
+    let expr: Expr = parse_terminated("-3! * (1 + 1)".chars()).unwrap();
+    assert_eq!(expr.eval(), -12);
+    // operators are printed without brackets, which round trips as long as the tree came from a parse
+    let output: String = print(&expr);
+    assert_eq!(parse_terminated::<Expr, _>(output.chars()).unwrap(), expr);
+
+    let mut input = "8 - 4 * 2)".chars().into_buf();
+    assert!(Expr::peek(&mut input.cursor()));
+    assert_eq!(Expr::parse(&mut input).unwrap().eval(), 0);
+    assert_eq!(input.next(), Some(')'));
+
+    let res: Result<Expr, _> = parse("* 1".chars());
+    assert!(res.is_err());
+}
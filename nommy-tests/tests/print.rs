@@ -0,0 +1,81 @@
+use nommy::{parse_terminated, print, text::*, Parse, Print};
+
+type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz_">;
+type Digits = AnyOf1<"0123456789">;
+
+#[derive(Debug, Parse, Print, PartialEq)]
+#[nommy(ignore = WhiteSpace)]
+struct Config {
+    sections: Vec<Section>,
+}
+
+#[derive(Debug, Parse, Print, PartialEq)]
+#[nommy(ignore = WhiteSpace)]
+struct Section {
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    #[nommy(parser = Letters)]
+    name: String,
+
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Parse, Print, PartialEq)]
+#[nommy(ignore = Space)]
+#[nommy(suffix = LineEnding)]
+struct Entry {
+    #[nommy(parser = Letters)]
+    key: String,
+
+    #[nommy(prefix = Tag<"=">)]
+    value: Value,
+}
+
+#[derive(Debug, Parse, Print, PartialEq)]
+#[nommy(ignore = Space)]
+enum Value {
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    List(#[nommy(inner_parser = Digits, seperated_by = Tag<",">, trailing = "maybe")] Vec<usize>),
+    Number(#[nommy(parser = Digits)] usize),
+    Bool(Bool),
+}
+
+#[derive(Debug, Parse, Print, PartialEq)]
+#[nommy(rename_all = "lowercase")]
+enum Bool {
+    True,
+    False,
+}
+
+#[derive(Debug, Parse, Print, PartialEq)]
+struct Wrapper<P> {
+    #[nommy(prefix = Tag<"<">, suffix = Tag<">">)]
+    inner: Option<P>,
+
+    #[nommy(skip)]
+    id: usize,
+}
+
+fn round_trip<P: Parse<char> + Print<char> + PartialEq + std::fmt::Debug>(input: &str) -> String {
+    let value: P = parse_terminated(input.chars()).unwrap();
+    let output: String = print(&value);
+    assert_eq!(parse_terminated::<P, _>(output.chars()).unwrap(), value);
+    output
+}
+
+fn main() {
+    let input = "[server]
+port = 8080
+debug = true
+[client]
+retries=[1, 2,3,]
+";
+    let output = round_trip::<Config>(input);
+    // values the parser ignores are printed wherever they're allowed
+    assert_eq!(
+        output,
+        "[ server ] port = 8080  \n debug = true  \n  [ client ] retries = [ 1 , 2 , 3]  \n   "
+    );
+
+    assert_eq!(round_trip::<Wrapper<Tag<"x">>>("<x>"), "<x>");
+    assert_eq!(round_trip::<Wrapper<Tag<"x">>>("<>"), "<>");
+}
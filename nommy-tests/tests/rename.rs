@@ -0,0 +1,55 @@
+use nommy::{parse, parse_terminated, text::*, Parse};
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(rename_all = "UPPERCASE")]
+enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    #[nommy(rename = "P")]
+    Short,
+}
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(rename_all = "kebab-case")]
+enum Keyword {
+    Let,
+    LetMut,
+    #[nommy(prefix = Tag<"fn">)]
+    Function,
+    Other(Tag<"?">),
+}
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(rename_all = "lowercase")]
+#[nommy(parse_type = u8)]
+enum Bytes {
+    Yes,
+    No,
+}
+
+fn main() {
+    let method: Method = parse_terminated("GET".chars()).unwrap();
+    assert_eq!(method, Method::Get);
+    let method: Method = parse_terminated("PUT".chars()).unwrap();
+    assert_eq!(method, Method::Put);
+    let method: Method = parse_terminated("PATCH".chars()).unwrap();
+    assert_eq!(method, Method::Patch);
+    let method: Method = parse_terminated("P".chars()).unwrap();
+    assert_eq!(method, Method::Short);
+    let res: Result<Method, _> = parse("get".chars());
+    assert!(res.is_err());
+
+    let keyword: Keyword = parse_terminated("let-mut".chars()).unwrap();
+    assert_eq!(keyword, Keyword::LetMut);
+    let keyword: Keyword = parse_terminated("let".chars()).unwrap();
+    assert_eq!(keyword, Keyword::Let);
+    let keyword: Keyword = parse_terminated("fn".chars()).unwrap();
+    assert_eq!(keyword, Keyword::Function);
+    let keyword: Keyword = parse_terminated("?".chars()).unwrap();
+    assert_eq!(keyword, Keyword::Other(Tag::<"?">));
+
+    let bytes: Bytes = parse_terminated("no".bytes()).unwrap();
+    assert_eq!(bytes, Bytes::No);
+}
@@ -0,0 +1,64 @@
+use nommy::{parse, text::*, Parse};
+
+type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(ignore = WhiteSpace)]
+struct Ident {
+    #[nommy(skip)]
+    id: usize,
+
+    #[nommy(parser = Letters)]
+    name: String,
+
+    #[nommy(default = Some("unresolved"))]
+    resolved: Option<&'static str>,
+}
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(ignore = WhiteSpace)]
+struct Pair(Ident, #[nommy(default = 2)] u8, Ident);
+
+#[derive(Debug, Parse, PartialEq)]
+enum Item {
+    #[nommy(prefix = Tag<"fn ">)]
+    Function {
+        #[nommy(parser = Letters)]
+        name: String,
+        #[nommy(skip)]
+        body: Vec<Ident>,
+    },
+    Unit(#[nommy(skip)] (), Tag<"()">),
+}
+
+fn main() {
+    let pair: Pair = parse("foo bar".chars()).unwrap();
+    assert_eq!(
+        pair,
+        Pair(
+            Ident {
+                id: 0,
+                name: "foo".to_string(),
+                resolved: Some("unresolved"),
+            },
+            2,
+            Ident {
+                id: 0,
+                name: "bar".to_string(),
+                resolved: Some("unresolved"),
+            },
+        )
+    );
+
+    let item: Item = parse("fn main".chars()).unwrap();
+    assert_eq!(
+        item,
+        Item::Function {
+            name: "main".to_string(),
+            body: vec![],
+        }
+    );
+
+    let item: Item = parse("()".chars()).unwrap();
+    assert_eq!(item, Item::Unit((), Tag::<"()">));
+}
@@ -6,7 +6,23 @@ fn tests() {
     t.pass("tests/enum.rs");
     t.pass("tests/mega.rs");
     t.pass("tests/http.rs");
+    t.pass("tests/http_bytes.rs");
     t.pass("tests/json.rs");
     t.pass("tests/lisp.rs");
     t.pass("tests/xml.rs");
+    t.pass("tests/skip.rs");
+    t.pass("tests/rename.rs");
+    t.pass("tests/longest.rs");
+    t.pass("tests/generics.rs");
+    t.pass("tests/binary.rs");
+    t.pass("tests/bits.rs");
+    t.pass("tests/print.rs");
+    t.pass("tests/generate.rs");
+    t.pass("tests/grammar.rs");
+    t.pass("tests/memoize.rs");
+    t.pass("tests/left_recursion.rs");
+    t.pass("tests/pratt.rs");
+    t.pass("tests/depth.rs");
+    t.pass("tests/budget.rs");
+    t.compile_fail("tests/ui/*.rs");
 }
@@ -0,0 +1,10 @@
+use nommy::Parse;
+
+#[derive(Parse)]
+#[nommy(parse_type = u8)]
+struct Flags {
+    #[nommy(bits = 16, parser = nommy::bytes::BigEndian<u16>)]
+    flags: u16,
+}
+
+fn main() {}
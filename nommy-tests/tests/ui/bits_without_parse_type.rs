@@ -0,0 +1,11 @@
+use nommy::Parse;
+
+#[derive(Parse)]
+struct Flags {
+    #[nommy(bits = 4)]
+    high: u8,
+    #[nommy(bits = 4)]
+    low: u8,
+}
+
+fn main() {}
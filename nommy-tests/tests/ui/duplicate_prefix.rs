@@ -0,0 +1,8 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+#[nommy(prefix = Tag<"(">)]
+#[nommy(prefix = Tag<"[">)]
+struct Bracketed(Tag<".">);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+#[nommy(parse_type = char)]
+enum Expr {
+    #[nommy(infix = Tag<"+">, precedence = 1)]
+    Add(Box<Expr>),
+    One(Tag<"1">),
+}
+
+fn main() {}
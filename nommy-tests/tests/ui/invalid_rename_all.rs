@@ -0,0 +1,9 @@
+use nommy::Parse;
+
+#[derive(Parse)]
+#[nommy(rename_all = "Title Case")]
+enum Keyword {
+    Let,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+#[nommy(parse_type = char)]
+enum Sum {
+    Add(Box<Sum>, #[nommy(prefix = Tag<"+">)] Tag<"1">),
+    One(Tag<"1">),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Clone, Parse)]
+enum Keyword {
+    #[nommy(memoize)]
+    Let(Tag<"let">),
+}
+
+fn main() {}
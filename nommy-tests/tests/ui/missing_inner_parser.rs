@@ -0,0 +1,9 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+struct List {
+    #[nommy(seperated_by = Tag<",">)]
+    items: Vec<Tag<".">>,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use nommy::Parse;
+
+#[derive(Parse)]
+#[nommy(prefix =)]
+struct Empty;
+
+fn main() {}
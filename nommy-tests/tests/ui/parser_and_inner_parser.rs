@@ -0,0 +1,10 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+struct List {
+    #[nommy(parser = Vec<Tag<".">>)]
+    #[nommy(inner_parser = Tag<".">)]
+    items: Vec<Tag<".">>,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use nommy::Print;
+
+#[derive(Print)]
+#[nommy(parse_type = u8)]
+struct Flags {
+    #[nommy(bits = 4)]
+    high: u8,
+    #[nommy(bits = 4)]
+    low: u8,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+#[nommy(rename_all = "lowercase")]
+struct Keyword(Tag<"let">);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+enum Keyword {
+    #[nommy(rename = "let")]
+    Let(Tag<" ">),
+}
+
+fn main() {}
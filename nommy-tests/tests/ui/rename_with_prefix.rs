@@ -0,0 +1,9 @@
+use nommy::Parse;
+
+#[derive(Parse)]
+enum Keyword {
+    #[nommy(rename = "let", prefix = nommy::text::Tag<"let">)]
+    Let,
+}
+
+fn main() {}
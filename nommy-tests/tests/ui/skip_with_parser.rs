@@ -0,0 +1,11 @@
+use nommy::{text::AnyOf1, Parse};
+
+type Letters = AnyOf1<"abc">;
+
+#[derive(Parse)]
+struct Ident {
+    #[nommy(skip, parser = Letters)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+struct List {
+    #[nommy(inner_parser = Tag<".">, trailing = "yes")]
+    items: Vec<Tag<".">>,
+}
+
+fn main() {}
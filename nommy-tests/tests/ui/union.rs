@@ -0,0 +1,9 @@
+use nommy::Parse;
+
+#[derive(Parse)]
+union Number {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
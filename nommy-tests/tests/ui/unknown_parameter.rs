@@ -0,0 +1,7 @@
+use nommy::Parse;
+
+#[derive(Parse)]
+#[nommy(prefx = Tag<"(">)]
+struct Empty;
+
+fn main() {}
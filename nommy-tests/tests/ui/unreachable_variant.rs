@@ -0,0 +1,13 @@
+#![deny(deprecated)]
+
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+enum Cmp {
+    Lt(Tag<"<">),
+    Le(Tag<"<=">),
+    #[nommy(prefix = Tag<"<<">)]
+    Shl,
+}
+
+fn main() {}
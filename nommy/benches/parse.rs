@@ -0,0 +1,140 @@
+#![allow(dead_code)]
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use nommy::{bytes, parse_terminated, text::*, IntoBuf, Parse, SliceBuf};
+
+#[derive(Debug, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+enum JSON {
+    #[nommy(prefix = Tag<"null">)]
+    Null,
+
+    #[nommy(prefix = Tag<"{">, suffix = Tag<"}">)]
+    Object(
+        #[nommy(inner_parser = Record)]
+        #[nommy(seperated_by = Tag<",">)]
+        Vec<Record>,
+    ),
+
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    List(
+        #[nommy(inner_parser = JSON)]
+        #[nommy(seperated_by = Tag<",">)]
+        Vec<JSON>,
+    ),
+
+    Number(#[nommy(parser = AnyOf1<"0123456789">)] String),
+
+    String(#[nommy(parser = StringParser)] String),
+}
+
+#[derive(Debug, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+struct Record {
+    #[nommy(parser = StringParser)]
+    #[nommy(suffix = Tag<":">)]
+    name: String,
+
+    value: JSON,
+}
+
+#[derive(Debug, Parse)]
+#[nommy(prefix = Tag<"\"">, suffix = Tag<"\"">)]
+#[nommy(parse_type = char)]
+struct StringParser(#[nommy(parser = WhileNot1<"\"">)] String);
+
+impl Into<String> for StringParser {
+    fn into(self) -> String {
+        self.0
+    }
+}
+
+type Identifier = AnyOf1<"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_">;
+
+#[derive(Debug, Parse)]
+#[nommy(parse_type = char)]
+struct Dashed {
+    dash: Option<Tag<"-">>,
+    foo: Tag<"foo">,
+}
+
+/// A list of `n` records, each with a few fields of every kind
+fn json_input(n: usize) -> String {
+    let mut input = String::from("[\n");
+    for i in 0..n {
+        if i > 0 {
+            input.push_str(",\n");
+        }
+        input.push_str(&format!(
+            r#"  {{ "id": "{}", "name": "record number {}", "score": {}, "tags": ["a", "bb", null], "nested": {{ "ok": null }} }}"#,
+            i,
+            i,
+            i * 7919
+        ));
+    }
+    input.push_str("\n]");
+    input
+}
+
+fn json(c: &mut Criterion) {
+    let input = json_input(200);
+    let mut group = c.benchmark_group("json");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("parse", |b| {
+        b.iter(|| parse_terminated::<JSON, _>(black_box(input.chars())).unwrap())
+    });
+    group.bench_function("peek", |b| {
+        b.iter(|| {
+            let mut buffer = black_box(input.chars()).into_buf();
+            assert!(JSON::peek(&mut buffer));
+        })
+    });
+    group.finish();
+}
+
+fn text(c: &mut Criterion) {
+    let digits = "0123456789".repeat(100) + " ";
+    let letters = "abcdefghij".repeat(100) + "\"";
+    let tags = "-foofoo".repeat(150) + "bar";
+    let identifier = "snake_case_Identifier_123_".repeat(40) + " ";
+
+    let mut group = c.benchmark_group("text");
+    group.bench_function("AnyOf1", |b| {
+        b.iter(|| nommy::parse::<AnyOf1<"0123456789">, _>(black_box(digits.chars())).unwrap())
+    });
+    group.bench_function("identifier", |b| {
+        b.iter(|| nommy::parse::<Identifier, _>(black_box(identifier.chars())).unwrap())
+    });
+    group.bench_function("WhileNot1", |b| {
+        b.iter(|| nommy::parse::<WhileNot1<"\"">, _>(black_box(letters.chars())).unwrap())
+    });
+    group.bench_function("Option<Tag>", |b| {
+        b.iter(|| nommy::parse::<Vec<Dashed>, _>(black_box(tags.chars())).unwrap())
+    });
+    group.finish();
+}
+
+fn bytes(c: &mut Criterion) {
+    let quoted = b"abcdefghij".repeat(100);
+    let quoted = [&quoted[..], b"\""].concat();
+
+    let mut group = c.benchmark_group("bytes");
+    group.bench_function("WhileNot1", |b| {
+        b.iter(|| {
+            let mut buffer = black_box(quoted.iter().copied()).into_buf();
+            bytes::WhileNot1::<b"\"">::parse(&mut buffer).unwrap()
+        })
+    });
+    group.bench_function("WhileNot1/SliceBuf", |b| {
+        b.iter(|| {
+            let mut buffer = SliceBuf::new(black_box(&quoted));
+            bytes::WhileNot1::<b"\"">::parse(&mut buffer).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, json, text, bytes);
+criterion_main!(benches);
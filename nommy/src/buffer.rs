@@ -1,5 +1,7 @@
 use std::{collections::VecDeque, marker::PhantomData};
 
+use crate::{context::Context, memo::Memo};
+
 /// `Buffer` is an extension to an [`Iterator`],
 /// with the ability to create a cursor over the iterator,
 /// which can infinitely read from the iterator, preserving the buffer's position
@@ -37,6 +39,101 @@ pub trait Buffer<T>: Iterator<Item = T> + Sized {
 
     /// Peek ahead by i spaces
     fn peek_ahead(&mut self, i: usize) -> Option<T>;
+
+    /// Reads the next value only if `f` accepts it, otherwise the buffer is left where it was.
+    /// Parsers that match one value at a time can use this to read each value once,
+    /// instead of peeking it on a cursor and then reading it again
+    ///
+    /// ```
+    /// use nommy::{Buffer, IntoBuf};
+    /// let mut buffer = "12a".chars().into_buf();
+    /// assert_eq!(buffer.next_if(char::is_ascii_digit), Some('1'));
+    /// assert_eq!(buffer.next_if(char::is_ascii_digit), Some('2'));
+    /// assert_eq!(buffer.next_if(char::is_ascii_digit), None);
+    /// assert_eq!(buffer.next(), Some('a'));
+    /// ```
+    fn next_if(&mut self, f: impl FnOnce(&T) -> bool) -> Option<T> {
+        let next = self.peek_ahead(0)?;
+        if f(&next) {
+            self.fast_forward(1);
+            Some(next)
+        } else {
+            None
+        }
+    }
+
+    /// The upcoming values, if the buffer can see them as one contiguous slice, such as [`SliceBuf`].
+    /// The slice doesn't have to reach the end of the input, so scans that get to the end of it
+    /// should carry on reading value by value. Returns `None` by default
+    fn lookahead(&mut self) -> Option<&[T]> {
+        None
+    }
+
+    /// Reads values into `output` for as long as `f` accepts them, returning how many were read.
+    /// Scans the whole [lookahead](Buffer::lookahead) window at once when there is one,
+    /// otherwise reads value by value
+    ///
+    /// ```
+    /// use nommy::{Buffer, SliceBuf};
+    /// let mut buffer = SliceBuf::new(b"abc123");
+    /// let mut output = Vec::new();
+    /// assert_eq!(buffer.read_while(u8::is_ascii_alphabetic, &mut output), 3);
+    /// assert_eq!(output, b"abc");
+    /// assert_eq!(buffer.next(), Some(b'1'));
+    /// ```
+    fn read_while(&mut self, mut f: impl FnMut(&T) -> bool, output: &mut impl Extend<T>) -> usize
+    where
+        T: Clone,
+    {
+        let mut read = 0;
+        if let Some(window) = self.lookahead() {
+            let n = window.iter().position(|t| !f(t)).unwrap_or(window.len());
+            let end_of_window = n == window.len();
+            output.extend(window[..n].iter().cloned());
+            self.fast_forward(n);
+            read += n;
+            if !end_of_window {
+                return read;
+            }
+        }
+        while let Some(t) = self.next_if(&mut f) {
+            output.extend(Some(t));
+            read += 1;
+        }
+        read
+    }
+
+    /// Skips over values for as long as `f` accepts them, returning how many were skipped.
+    /// The same as [`Buffer::read_while`] but without keeping the values
+    fn advance_while(&mut self, mut f: impl FnMut(&T) -> bool) -> usize {
+        let mut read = 0;
+        if let Some(window) = self.lookahead() {
+            let n = window.iter().position(|t| !f(t)).unwrap_or(window.len());
+            let end_of_window = n == window.len();
+            self.fast_forward(n);
+            read += n;
+            if !end_of_window {
+                return read;
+            }
+        }
+        while self.next_if(&mut f).is_some() {
+            read += 1;
+        }
+        read
+    }
+
+    /// The [`Memo`] that memoized parsers save their results in, along with how far into the input the buffer is.
+    /// Returns `None` by default, which parses memoized types as normal
+    fn memo(&mut self) -> Option<(usize, &mut Memo)> {
+        None
+    }
+
+    /// The [`Context`] that limits how deeply values can nest and how many reads can be made.
+    /// Buffers that have one should call [`Context::step`] before each read.
+    /// Returns `None` by default, which leaves the parse unlimited
+    fn context(&mut self) -> Option<&mut Context> {
+        None
+    }
 }
 
 /// `IntoBuf` is the equivalent of [`IntoIterator`] for a basic implementation of [`Buffer`]
@@ -55,21 +152,34 @@ impl<I: IntoIterator> IntoBuf for I {
     }
 }
 
+/// The capacity above which [`Buf`] releases the memory of its lookahead once it's mostly been read
+const SHRINK_CAPACITY: usize = 1024;
+
 /// Buf is the standard implementation of [`Buffer`]. It stores any peeked data into a [`VecDeque`].
 /// Any values peeked will be stored into the [`VecDeque`], and next will either call [`VecDeque::pop_front`]
 /// or [`Iterator::next`] on the inner iter
 pub struct Buf<I: Iterator> {
     iter: I,
     buffer: VecDeque<I::Item>,
+    /// How many values have been read
+    offset: usize,
+    memo: Memo,
+    context: Context,
 }
 
 impl<I: Iterator> Iterator for Buf<I> {
     type Item = I::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.buffer
+        if !self.context.step() {
+            return None;
+        }
+        let output = self
+            .buffer
             .pop_front()
-            .map_or_else(|| self.iter.next(), Some)
+            .map_or_else(|| self.iter.next(), Some)?;
+        self.offset += 1;
+        Some(output)
     }
 }
 
@@ -79,8 +189,18 @@ impl<I: Iterator> Buf<I> {
         Self {
             iter: iter.into_iter(),
             buffer: VecDeque::new(),
+            offset: 0,
+            memo: Memo::new(),
+            context: Context::new(),
         }
     }
+
+    /// Replaces the default [`Context`], to change the limits of the parse
+    #[must_use]
+    pub fn with_context(mut self, context: Context) -> Self {
+        self.context = context;
+        self
+    }
 }
 
 impl<I: Iterator> Buffer<I::Item> for Buf<I>
@@ -94,20 +214,27 @@ where
 
     fn fast_forward(&mut self, n: usize) {
         let len = self.buffer.len();
-        if len <= n {
+        if len < n {
             self.buffer.clear();
-            for _ in 0..(n - len) {
-                if self.iter.next().is_none() {
-                    break;
-                }
-            }
+            self.iter.nth(n - len - 1);
         } else {
-            self.buffer.rotate_left(n);
-            self.buffer.truncate(len - n);
+            self.buffer.drain(..n);
+        }
+        self.offset += n;
+        self.memo.forget_before(self.offset);
+
+        // give back the memory from a long lookahead once it's been read
+        if self.buffer.capacity() > SHRINK_CAPACITY
+            && self.buffer.len() < self.buffer.capacity() / 4
+        {
+            self.buffer.shrink_to_fit();
         }
     }
 
     fn peek_ahead(&mut self, i: usize) -> Option<I::Item> {
+        if !self.context.step() {
+            return None;
+        }
         if i < self.buffer.len() {
             Some(self.buffer[i].clone())
         } else {
@@ -121,6 +248,32 @@ where
             Some(output)
         }
     }
+
+    /// Only buffers the value if `f` rejects it
+    fn next_if(&mut self, f: impl FnOnce(&I::Item) -> bool) -> Option<I::Item> {
+        if !self.context.step() {
+            return None;
+        }
+        let next = self
+            .buffer
+            .pop_front()
+            .map_or_else(|| self.iter.next(), Some)?;
+        if f(&next) {
+            self.offset += 1;
+            Some(next)
+        } else {
+            self.buffer.push_front(next);
+            None
+        }
+    }
+
+    fn memo(&mut self) -> Option<(usize, &mut Memo)> {
+        Some((self.offset, &mut self.memo))
+    }
+
+    fn context(&mut self) -> Option<&mut Context> {
+        Some(&mut self.context)
+    }
 }
 
 /// `Cursor` is a [`Buffer`] that non-destructively reads from it's parent's buffer using [`Buffer::peek_ahead`]
@@ -133,7 +286,7 @@ pub struct Cursor<'a, T, B: Buffer<T>> {
 }
 
 impl<'a, T, B: Buffer<T>> Cursor<'a, T, B> {
-    fn new(buf: &'a mut B) -> Self {
+    pub(crate) fn new(buf: &'a mut B) -> Self {
         Self {
             buf,
             base: 0,
@@ -182,6 +335,22 @@ impl<'a, T, B: Buffer<T>> Buffer<T> for Cursor<'a, T, B> {
         self.buf.peek_ahead(self.base + self.index + i)
     }
 
+    fn lookahead(&mut self) -> Option<&[T]> {
+        let offset = self.base + self.index;
+        let window = self.buf.lookahead()?;
+        Some(window.get(offset..).unwrap_or_default())
+    }
+
+    fn memo(&mut self) -> Option<(usize, &mut Memo)> {
+        let offset = self.base + self.index;
+        let (base, memo) = self.buf.memo()?;
+        Some((base + offset, memo))
+    }
+
+    fn context(&mut self) -> Option<&mut Context> {
+        self.buf.context()
+    }
+
     type CursorBase = B;
     fn cursor(&mut self) -> Cursor<T, Self::CursorBase> {
         Cursor {
@@ -203,6 +372,97 @@ impl<'a, T, B: Buffer<T>> Iterator for Cursor<'a, T, B> {
     }
 }
 
+/// `SliceBuf` is a [`Buffer`] that reads from a slice.
+/// Since all of the input is in memory, parsers can scan it in bulk using [`Buffer::lookahead`]
+///
+/// ```
+/// use nommy::{Parse, SliceBuf, bytes::WhileNot1};
+/// let mut buffer = SliceBuf::new(b"key=value");
+/// let key: Vec<u8> = WhileNot1::<b"=">::parse(&mut buffer).unwrap().into();
+/// assert_eq!(key, b"key");
+/// assert_eq!(buffer.remaining(), b"=value");
+/// ```
+pub struct SliceBuf<'a, T> {
+    slice: &'a [T],
+    pos: usize,
+    memo: Memo,
+    context: Context,
+}
+
+impl<'a, T> SliceBuf<'a, T> {
+    /// Create a new `SliceBuf` that starts at the beginning of the slice
+    #[must_use]
+    pub fn new(slice: &'a [T]) -> Self {
+        Self {
+            slice,
+            pos: 0,
+            memo: Memo::new(),
+            context: Context::new(),
+        }
+    }
+
+    /// Replaces the default [`Context`], to change the limits of the parse
+    #[must_use]
+    pub fn with_context(mut self, context: Context) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// The values that haven't been read yet
+    #[must_use]
+    pub fn remaining(&self) -> &'a [T] {
+        &self.slice[self.pos..]
+    }
+}
+
+impl<'a, T: Clone> Iterator for SliceBuf<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if !self.context.step() {
+            return None;
+        }
+        let output = self.slice.get(self.pos)?.clone();
+        self.pos += 1;
+        Some(output)
+    }
+}
+
+impl<'a, T: Clone> Buffer<T> for SliceBuf<'a, T> {
+    type CursorBase = Self;
+    fn cursor(&mut self) -> Cursor<T, Self::CursorBase> {
+        Cursor::new(self)
+    }
+
+    fn fast_forward(&mut self, n: usize) {
+        self.pos = self.slice.len().min(self.pos + n);
+        self.memo.forget_before(self.pos);
+    }
+
+    fn peek_ahead(&mut self, i: usize) -> Option<T> {
+        if !self.context.step() {
+            return None;
+        }
+        self.slice.get(self.pos + i).cloned()
+    }
+
+    /// Counts as a single step of the [`Context`], however much of the slice is scanned
+    fn lookahead(&mut self) -> Option<&[T]> {
+        if !self.context.step() {
+            return None;
+        }
+        Some(self.remaining())
+    }
+
+    fn memo(&mut self) -> Option<(usize, &mut Memo)> {
+        Some((self.pos, &mut self.memo))
+    }
+
+    fn context(&mut self) -> Option<&mut Context> {
+        Some(&mut self.context)
+    }
+}
+
 // use std::io::Read;
 
 // /// Implements [`Buffer`] for types that implement [`Read`]
@@ -327,9 +587,9 @@ impl<'a, T, B: Buffer<T>> Iterator for Cursor<'a, T, B> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{IntoBuf};
+    use crate::IntoBuf;
 
-    use super::Buffer;
+    use super::{Buffer, SliceBuf};
 
     #[test]
     fn cursor_isolation() {
@@ -372,6 +632,70 @@ mod tests {
         assert!(buffer.buffer.is_empty());
     }
 
+    #[test]
+    fn fast_forward() {
+        let mut buffer = (0..).into_buf();
+        assert_eq!(buffer.peek_ahead(2), Some(2));
+
+        // within the lookahead
+        buffer.fast_forward(1);
+        assert_eq!(buffer.buffer.len(), 2);
+        assert_eq!(buffer.peek_ahead(0), Some(1));
+
+        // past the end of the lookahead
+        buffer.fast_forward(5);
+        assert!(buffer.buffer.is_empty());
+        assert_eq!(buffer.next(), Some(6));
+
+        // past the end of the input
+        let mut buffer = (0..3).into_buf();
+        buffer.fast_forward(5);
+        assert_eq!(buffer.next(), None);
+    }
+
+    #[test]
+    fn fast_forward_releases_memory() {
+        let mut buffer = (0..).into_buf();
+        assert_eq!(buffer.peek_ahead(10_000), Some(10_000));
+        assert!(buffer.buffer.capacity() > 10_000);
+
+        buffer.fast_forward(9_990);
+        assert!(buffer.buffer.capacity() < 1_000);
+        assert_eq!(buffer.next(), Some(9_990));
+    }
+
+    #[test]
+    fn slice_lookahead() {
+        let mut buffer = SliceBuf::new(&[1, 2, 3, 4, 5]);
+        assert_eq!(buffer.next(), Some(1));
+
+        let mut cursor = buffer.cursor();
+        cursor.fast_forward(1);
+        assert_eq!(cursor.lookahead(), Some(&[3, 4, 5][..]));
+        cursor.fast_forward(5);
+        assert_eq!(cursor.lookahead(), Some(&[][..]));
+
+        assert_eq!(buffer.lookahead(), Some(&[2, 3, 4, 5][..]));
+        assert_eq!((0..).into_buf().lookahead(), None);
+    }
+
+    #[test]
+    fn read_while() {
+        let mut output = String::new();
+        let mut buffer = "abc123".chars().into_buf();
+        assert_eq!(buffer.read_while(char::is_ascii_alphabetic, &mut output), 3);
+        assert_eq!(buffer.advance_while(char::is_ascii_digit), 3);
+        assert_eq!(output, "abc");
+
+        let chars: Vec<char> = "abc123".chars().collect();
+        let mut buffer = SliceBuf::new(&chars);
+        let mut cursor = buffer.cursor();
+        assert_eq!(cursor.advance_while(char::is_ascii_alphabetic), 3);
+        assert_eq!(cursor.read_while(char::is_ascii_digit, &mut output), 3);
+        assert_eq!(output, "abc123");
+        assert_eq!(cursor.next(), None);
+    }
+
     // #[test]
     // fn bufread() {
     //     let read: &[u8] = b"Hello World!";
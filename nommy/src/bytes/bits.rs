@@ -0,0 +1,192 @@
+use std::convert::TryFrom;
+
+use crate::{eyre, Buffer, Cursor, Parse};
+
+/// `BitBuffer` is a [`Buffer`] over the individual bits of a [`Buffer<u8>`], most significant bit first.
+///
+/// Reading bits does not consume the underlying buffer.
+/// Once the bit fields have been read, [`BitBuffer::realign`] consumes the bytes read
+/// so byte parsers can carry on from there.
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::{BitBuffer, Bits}};
+/// let mut buffer = vec![0b0100_0101, 0xff].into_buf();
+/// let mut bits = BitBuffer::new(&mut buffer);
+/// let version: u64 = Bits::<4>::parse(&mut bits).unwrap().into();
+/// let ihl: u64 = Bits::<4>::parse(&mut bits).unwrap().into();
+/// bits.realign().unwrap();
+///
+/// assert_eq!((version, ihl), (4, 5));
+/// assert_eq!(buffer.next(), Some(0xff));
+/// ```
+pub struct BitBuffer<'a, B: Buffer<u8>> {
+    buf: &'a mut B,
+    bit: usize,
+}
+
+impl<'a, B: Buffer<u8>> BitBuffer<'a, B> {
+    /// Create a new `BitBuffer` starting at the first bit of `buf`
+    pub fn new(buf: &'a mut B) -> Self {
+        Self::with_offset(buf, 0)
+    }
+
+    /// Create a new `BitBuffer` starting `bit` bits into `buf`
+    pub fn with_offset(buf: &'a mut B, bit: usize) -> Self {
+        Self { buf, bit }
+    }
+
+    /// Returns how many bits have been read from the start of the underlying buffer
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        self.bit
+    }
+
+    /// Consumes the bytes that have been read from the underlying buffer
+    ///
+    /// # Errors
+    /// If the bits read do not end on a byte boundary
+    pub fn realign(self) -> eyre::Result<()> {
+        if self.bit % 8 == 0 {
+            self.buf.fast_forward(self.bit / 8);
+            Ok(())
+        } else {
+            Err(eyre::eyre!(
+                "bit fields are not byte aligned, {} bits left over",
+                self.bit % 8
+            ))
+        }
+    }
+}
+
+impl<'a, B: Buffer<u8>> Iterator for BitBuffer<'a, B> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        let output = self.peek_ahead(0);
+        self.bit += 1;
+        output
+    }
+}
+
+impl<'a, B: Buffer<u8>> Buffer<bool> for BitBuffer<'a, B> {
+    type CursorBase = Self;
+    fn cursor(&mut self) -> Cursor<bool, Self::CursorBase> {
+        Cursor::new(self)
+    }
+
+    fn fast_forward(&mut self, n: usize) {
+        self.bit += n;
+    }
+
+    fn peek_ahead(&mut self, i: usize) -> Option<bool> {
+        let bit = self.bit + i;
+        let byte = self.buf.peek_ahead(bit / 8)?;
+        Some(byte >> (7 - bit % 8) & 1 == 1)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// `Bits` is a generic type that implements [`Parse`] to read an `N` bit unsigned integer
+/// out of a [`BitBuffer`], most significant bit first. `N` must be at most 64
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::{BitBuffer, Bits}};
+/// let mut buffer = vec![0b1010_0000].into_buf();
+/// let mut bits = BitBuffer::new(&mut buffer);
+/// let n: u64 = Bits::<3>::parse(&mut bits).unwrap().into();
+/// assert_eq!(n, 0b101);
+/// ```
+pub struct Bits<const N: usize>(u64);
+
+impl<const N: usize> Parse<bool> for Bits<N> {
+    fn parse(input: &mut impl Buffer<bool>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            if N > 64 {
+                return Err(eyre::eyre!("cannot parse {} bits into a u64", N));
+            }
+            let mut output = 0;
+            for i in 0..N {
+                let bit = input.next().ok_or_else(|| {
+                    eyre::eyre!("error parsing {} bits, reached EOF after {} bits", N, i)
+                })?;
+                output = output << 1 | u64::from(bit);
+            }
+            Ok(Self(output))
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<bool>) -> bool {
+        N <= 64 && input.take(N).count() == N
+    }
+}
+
+impl<const N: usize> From<Bits<N>> for u64 {
+    fn from(v: Bits<N>) -> Self {
+        v.0
+    }
+}
+
+impl From<Bits<1>> for bool {
+    fn from(v: Bits<1>) -> Self {
+        v.0 == 1
+    }
+}
+
+macro_rules! bits_try_from {
+    ($($t:ty),*) => {$(
+        impl<const N: usize> TryFrom<Bits<N>> for $t {
+            type Error = std::num::TryFromIntError;
+            fn try_from(v: Bits<N>) -> Result<Self, Self::Error> {
+                Self::try_from(v.0)
+            }
+        }
+    )*};
+}
+
+bits_try_from!(u8, u16, u32, usize);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use crate::IntoBuf;
+
+    #[test]
+    fn read_bits() {
+        let mut input = vec![0b1011_0011, 0b1100_0000].into_buf();
+        let mut bits = BitBuffer::new(&mut input);
+        assert_eq!(u64::from(Bits::<1>::parse(&mut bits).unwrap()), 0b1);
+        assert_eq!(u64::from(Bits::<3>::parse(&mut bits).unwrap()), 0b011);
+        assert_eq!(u64::from(Bits::<6>::parse(&mut bits).unwrap()), 0b00_1111);
+        assert_eq!(bits.offset(), 10);
+        assert!(bits.realign().is_err());
+    }
+
+    #[test]
+    fn realign() {
+        let mut input = vec![0xab, 0xcd, 0xef].into_buf();
+        let mut bits = BitBuffer::new(&mut input);
+        let n: u16 = Bits::<16>::parse(&mut bits).unwrap().try_into().unwrap();
+        assert_eq!(n, 0xabcd);
+        bits.realign().unwrap();
+        assert_eq!(input.next(), Some(0xef));
+    }
+
+    #[test]
+    fn cursor() {
+        let mut input = vec![0b1000_0000].into_buf();
+        let mut bits = BitBuffer::new(&mut input);
+        assert!(!Bits::<9>::peek(&mut bits.cursor()));
+        assert!(Bits::<8>::peek(&mut bits.cursor()));
+        assert!(bool::from(Bits::<1>::parse(&mut bits).unwrap()));
+    }
+
+    #[test]
+    fn try_into_overflow() {
+        let mut input = vec![0xff, 0xff].into_buf();
+        let mut bits = BitBuffer::new(&mut input);
+        let res: Result<u8, _> = Bits::<9>::parse(&mut bits).unwrap().try_into();
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::mem::size_of;
+
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Parse, Print,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// `BigEndian` is a generic type that implements [`Parse`] to read a big-endian encoded number
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::BigEndian};
+/// let mut buffer = [0x12, 0x34].iter().copied().into_buf();
+/// let n: u16 = BigEndian::<u16>::parse(&mut buffer).unwrap().into();
+/// assert_eq!(n, 0x1234);
+/// ```
+pub struct BigEndian<T>(T);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// `LittleEndian` is a generic type that implements [`Parse`] to read a little-endian encoded number
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::LittleEndian};
+/// let mut buffer = [0x12, 0x34].iter().copied().into_buf();
+/// let n: u16 = LittleEndian::<u16>::parse(&mut buffer).unwrap().into();
+/// assert_eq!(n, 0x3412);
+/// ```
+pub struct LittleEndian<T>(T);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// `NativeEndian` is a generic type that implements [`Parse`] to read a number
+/// encoded with the target platform's endianness
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::NativeEndian};
+/// let mut buffer = 1234_u32.to_ne_bytes().to_vec().into_buf();
+/// let n: u32 = NativeEndian::<u32>::parse(&mut buffer).unwrap().into();
+/// assert_eq!(n, 1234);
+/// ```
+pub struct NativeEndian<T>(T);
+
+/// Reads exactly `N` bytes from the input
+fn read_bytes<const N: usize>(input: &mut impl Buffer<u8>, name: &str) -> eyre::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = input.next().ok_or_else(|| {
+            eyre::eyre!(
+                "error parsing {}, reached EOF after {} of {} bytes",
+                name,
+                i,
+                N
+            )
+        })?;
+    }
+    Ok(bytes)
+}
+
+macro_rules! endian {
+    ($endian:ident, $from_bytes:ident, $to_bytes:ident: $($t:ty),*) => {$(
+        impl Parse<u8> for $endian<$t> {
+            fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+                crate::context::check(input, |input| {
+                    let bytes = read_bytes::<{ size_of::<$t>() }>(
+                        input,
+                        concat!(stringify!($endian), "<", stringify!($t), ">"),
+                    )?;
+                    Ok(Self(<$t>::$from_bytes(bytes)))
+                })
+            }
+
+            fn peek(input: &mut impl Buffer<u8>) -> bool {
+                input.take(size_of::<$t>()).count() == size_of::<$t>()
+            }
+        }
+
+        impl From<$endian<$t>> for $t {
+            fn from(v: $endian<$t>) -> Self {
+                v.0
+            }
+        }
+
+        impl From<$t> for $endian<$t> {
+            fn from(n: $t) -> Self {
+                Self(n)
+            }
+        }
+
+        impl Print<u8> for $endian<$t> {
+            fn print(&self, output: &mut impl Extend<u8>) {
+                output.extend(self.0.$to_bytes());
+            }
+        }
+
+        impl Describe<u8> for $endian<$t> {
+            fn describe(_: &mut Grammar) -> Rule {
+                Rule::Special(concat!(stringify!($endian), "<", stringify!($t), ">").to_string())
+            }
+        }
+    )*};
+}
+
+endian!(BigEndian, from_be_bytes, to_be_bytes: u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+endian!(LittleEndian, from_le_bytes, to_le_bytes: u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+endian!(NativeEndian, from_ne_bytes, to_ne_bytes: u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, print, IntoBuf};
+
+    #[test]
+    fn parse_integers() {
+        let mut input = [0x01, 0x02, 0x03, 0x04, 0x01, 0x02, 0x03, 0x04]
+            .iter()
+            .copied()
+            .into_buf();
+        let be: u32 = BigEndian::<u32>::parse(&mut input).unwrap().into();
+        let le: u32 = LittleEndian::<u32>::parse(&mut input).unwrap().into();
+        assert_eq!(be, 0x0102_0304);
+        assert_eq!(le, 0x0403_0201);
+        assert!(input.next().is_none());
+    }
+
+    #[test]
+    fn parse_signed() {
+        let n: i16 = parse::<BigEndian<i16>, _>(vec![0xff, 0xfe]).unwrap().into();
+        assert_eq!(n, -2);
+        let n: i128 = parse::<LittleEndian<i128>, _>((-5_i128).to_le_bytes().to_vec())
+            .unwrap()
+            .into();
+        assert_eq!(n, -5);
+    }
+
+    #[test]
+    fn parse_floats() {
+        let n: f64 = parse::<BigEndian<f64>, _>(1.5_f64.to_be_bytes().to_vec())
+            .unwrap()
+            .into();
+        assert_eq!(n.to_bits(), 1.5_f64.to_bits());
+        let n: f32 = parse::<NativeEndian<f32>, _>((-0.25_f32).to_ne_bytes().to_vec())
+            .unwrap()
+            .into();
+        assert_eq!(n.to_bits(), (-0.25_f32).to_bits());
+    }
+
+    #[test]
+    fn print_numbers() {
+        let bytes: Vec<u8> = print(&BigEndian::from(0x0102_u16));
+        assert_eq!(bytes, [0x01, 0x02]);
+        let bytes: Vec<u8> = print(&LittleEndian::from(-2_i32));
+        assert_eq!(bytes, [0xfe, 0xff, 0xff, 0xff]);
+        let n: f64 = parse::<BigEndian<f64>, _>(print::<u8, _, Vec<u8>>(&BigEndian::from(1.5_f64)))
+            .unwrap()
+            .into();
+        assert_eq!(n.to_bits(), 1.5_f64.to_bits());
+    }
+
+    #[test]
+    fn peek() {
+        let mut input = vec![0x01, 0x02, 0x03].into_buf();
+        assert!(BigEndian::<u16>::peek(&mut input.cursor()));
+        assert!(!BigEndian::<u32>::peek(&mut input.cursor()));
+    }
+
+    #[test]
+    fn parse_eof() {
+        let res: Result<BigEndian<u32>, _> = parse(vec![0x01, 0x02]);
+        assert_eq!(
+            format!("{}", res.unwrap_err()),
+            "error parsing BigEndian<u32>, reached EOF after 2 of 4 bytes"
+        );
+    }
+}
@@ -1,7 +1,179 @@
-use crate::{eyre, Buffer, Parse};
+use std::{convert::TryFrom, str::FromStr, string::FromUtf8Error};
+
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Generate, Generator, Parse, Print, Vec1,
+};
 
 use super::OneOf;
 
+#[derive(Debug, Clone, PartialEq)]
+/// `AnyOf` is a generic type that implements [`Parse`] to match many bytes within the given string
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::AnyOf};
+/// let mut buffer = "-_-.".bytes().into_buf();
+/// let c: Vec<u8> = AnyOf::<b"-_">::parse(&mut buffer).unwrap().into();
+/// assert_eq!(c, b"-_-");
+/// ```
+pub struct AnyOf<const BYTES: &'static [u8]>(Vec<u8>);
+
+impl<const BYTES: &'static [u8]> From<AnyOf<BYTES>> for Vec<u8> {
+    fn from(v: AnyOf<BYTES>) -> Self {
+        v.0
+    }
+}
+
+impl<const BYTES: &'static [u8]> TryFrom<AnyOf<BYTES>> for String {
+    type Error = FromUtf8Error;
+    fn try_from(v: AnyOf<BYTES>) -> Result<Self, Self::Error> {
+        Self::from_utf8(v.0)
+    }
+}
+
+impl<const BYTES: &'static [u8]> From<Vec<u8>> for AnyOf<BYTES> {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const BYTES: &'static [u8]> From<String> for AnyOf<BYTES> {
+    fn from(s: String) -> Self {
+        Self(s.into_bytes())
+    }
+}
+
+impl<const BYTES: &'static [u8]> Print<u8> for AnyOf<BYTES> {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(self.0.iter().copied());
+    }
+}
+
+impl<const BYTES: &'static [u8]> Generate<u8> for AnyOf<BYTES> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<u8>) {
+        Vec::<OneOf<BYTES>>::generate(gen, output);
+    }
+}
+
+impl<const BYTES: &'static [u8]> Describe<u8> for AnyOf<BYTES> {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        Rule::repeat(OneOf::<BYTES>::describe(grammar), 0)
+    }
+}
+
+impl<const BYTES: &'static [u8]> Parse<u8> for AnyOf<BYTES> {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            let mut output = Vec::new();
+            input.read_while(|&c| OneOf::<BYTES>::contains(c), &mut output);
+            Ok(Self(output))
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        crate::context::check_peek(input, |input| {
+            input.advance_while(|&c| OneOf::<BYTES>::contains(c));
+            true
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// `WhileNot1` is a generic type that implements [`Parse`] to match many bytes not within the given string
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::WhileNot1};
+/// let mut buffer = "-_-.".bytes().into_buf();
+/// let c: Vec<u8> = WhileNot1::<b".">::parse(&mut buffer).unwrap().into();
+/// assert_eq!(c, b"-_-");
+/// ```
+pub struct WhileNot1<const BYTES: &'static [u8]>(Vec<u8>);
+
+impl<const BYTES: &'static [u8]> From<WhileNot1<BYTES>> for Vec<u8> {
+    fn from(v: WhileNot1<BYTES>) -> Self {
+        v.0
+    }
+}
+
+impl<const BYTES: &'static [u8]> TryFrom<WhileNot1<BYTES>> for String {
+    type Error = FromUtf8Error;
+    fn try_from(v: WhileNot1<BYTES>) -> Result<Self, Self::Error> {
+        Self::from_utf8(v.0)
+    }
+}
+
+impl<const BYTES: &'static [u8]> From<Vec<u8>> for WhileNot1<BYTES> {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const BYTES: &'static [u8]> From<String> for WhileNot1<BYTES> {
+    fn from(s: String) -> Self {
+        Self(s.into_bytes())
+    }
+}
+
+impl<const BYTES: &'static [u8]> Print<u8> for WhileNot1<BYTES> {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(self.0.iter().copied());
+    }
+}
+
+/// Generates ASCII letters that aren't in `BYTES`, or any other printable ASCII byte if every letter is,
+/// or any other byte if every printable one is. Panics if every byte is in `BYTES`, since then nothing matches
+impl<const BYTES: &'static [u8]> Generate<u8> for WhileNot1<BYTES> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<u8>) {
+        let mut bytes: Vec<u8> = (b'a'..=b'z')
+            .chain(b'A'..=b'Z')
+            .filter(|b| !BYTES.contains(b))
+            .collect();
+        if bytes.is_empty() {
+            bytes = (b' '..=b'~').filter(|b| !BYTES.contains(b)).collect();
+        }
+        if bytes.is_empty() {
+            bytes = (0..=u8::MAX).filter(|b| !BYTES.contains(b)).collect();
+        }
+        assert!(
+            !bytes.is_empty(),
+            "`WhileNot1` can't be generated when every byte is excluded"
+        );
+        for _ in 0..=gen.repeat() {
+            output.extend(Some(*gen.rng().choose(&bytes)));
+        }
+    }
+}
+
+impl<const BYTES: &'static [u8]> Describe<u8> for WhileNot1<BYTES> {
+    fn describe(_: &mut Grammar) -> Rule {
+        let chars = Rule::CharSet {
+            chars: BYTES.iter().copied().map(char::from).collect(),
+            negated: true,
+        };
+        Rule::repeat(chars, 1)
+    }
+}
+
+impl<const BYTES: &'static [u8]> Parse<u8> for WhileNot1<BYTES> {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            let mut output = Vec::new();
+            if input.read_while(|&c| !OneOf::<BYTES>::contains(c), &mut output) == 0 {
+                Err(eyre::eyre!("no characters found"))
+            } else {
+                Ok(Self(output))
+            }
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        crate::context::check_peek(input, |input| {
+            input.advance_while(|&c| !OneOf::<BYTES>::contains(c)) > 0
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// `AnyOf1` is a generic type that implements [`Parse`] to match many characters within the given string
 ///
@@ -19,37 +191,104 @@ impl<const BYTES: &'static [u8]> From<AnyOf1<BYTES>> for Vec<u8> {
     }
 }
 
+impl<const BYTES: &'static [u8]> TryFrom<AnyOf1<BYTES>> for String {
+    type Error = FromUtf8Error;
+    fn try_from(v: AnyOf1<BYTES>) -> Result<Self, Self::Error> {
+        Self::from_utf8(v.0)
+    }
+}
+
+impl<const BYTES: &'static [u8]> From<Vec<u8>> for AnyOf1<BYTES> {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const BYTES: &'static [u8]> From<String> for AnyOf1<BYTES> {
+    fn from(s: String) -> Self {
+        Self(s.into_bytes())
+    }
+}
+
+impl<const BYTES: &'static [u8]> TryFrom<AnyOf1<BYTES>> for usize {
+    type Error = <Self as FromStr>::Err;
+    fn try_from(v: AnyOf1<BYTES>) -> Result<Self, Self::Error> {
+        Self::from_str(&String::from_utf8_lossy(&v.0))
+    }
+}
+
+/// Writes the number in decimal
+impl<const BYTES: &'static [u8]> From<usize> for AnyOf1<BYTES> {
+    fn from(n: usize) -> Self {
+        Self(n.to_string().into_bytes())
+    }
+}
+
+impl<const BYTES: &'static [u8]> Print<u8> for AnyOf1<BYTES> {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(self.0.iter().copied());
+    }
+}
+
+impl<const BYTES: &'static [u8]> Generate<u8> for AnyOf1<BYTES> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<u8>) {
+        Vec1::<OneOf<BYTES>>::generate(gen, output);
+    }
+}
+
+impl<const BYTES: &'static [u8]> Describe<u8> for AnyOf1<BYTES> {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        Rule::repeat(OneOf::<BYTES>::describe(grammar), 1)
+    }
+}
+
 impl<const BYTES: &'static [u8]> Parse<u8> for AnyOf1<BYTES> {
     fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
-        let mut output = Vec::new();
+        crate::context::check(input, |input| {
+            let mut output = Vec::new();
+            if input.read_while(|&c| OneOf::<BYTES>::contains(c), &mut output) == 0 {
+                Err(eyre::eyre!("no characters found"))
+            } else {
+                Ok(Self(output))
+            }
+        })
+    }
 
-        while OneOf::<BYTES>::peek(&mut input.cursor()) {
-            output.push(
-                OneOf::<BYTES>::parse(input)
-                    .expect("peek succeeded but parse failed")
-                    .into(),
-            );
-        }
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        crate::context::check_peek(input, |input| {
+            input.advance_while(|&c| OneOf::<BYTES>::contains(c)) > 0
+        })
+    }
+}
 
-        if output.is_empty() {
-            Err(eyre::eyre!("no characters found"))
-        } else {
-            Ok(Self(output))
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntoBuf;
+
+    #[test]
+    fn any_of_peek() {
+        let mut buffer = b"1024$".to_vec().into_buf();
+        let mut cursor = buffer.cursor();
+        assert!(AnyOf::<b"0123456789">::peek(&mut cursor));
+        assert_eq!(cursor.next(), Some(b'$'));
     }
 
-    fn peek(input: &mut impl Buffer<u8>) -> bool {
-        if !OneOf::<BYTES>::peek(input) {
-            return false;
-        }
-        loop {
-            let mut cursor = input.cursor();
-            if !OneOf::<BYTES>::peek(&mut cursor) {
-                break;
-            }
-            let pos = cursor.position();
-            input.fast_forward(pos);
+    /// Every printable ASCII character
+    type NotPrintable = WhileNot1<b" !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~">;
+
+    #[test]
+    fn while_not_generates_outside_printable_ascii() {
+        let mut gen = Generator::new(3);
+        for _ in 0..20 {
+            let bytes: Vec<u8> = crate::generate::<NotPrintable, _, _>(&mut gen);
+            assert!(!bytes.is_empty());
+            assert!(
+                bytes.iter().all(|b| !(b' '..=b'~').contains(b)),
+                "{:?}",
+                bytes
+            );
+            crate::parse_terminated::<NotPrintable, _>(bytes).unwrap();
         }
-        true
     }
 }
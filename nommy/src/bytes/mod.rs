@@ -6,3 +6,192 @@ mod one_of;
 pub use one_of::*;
 mod many;
 pub use many::*;
+mod endian;
+mod set;
+pub use endian::*;
+mod varint;
+pub use varint::*;
+mod bits;
+pub use bits::*;
+mod strings;
+pub use strings::*;
+
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Generate, Generator, Parse, Print,
+};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+/// Parses newline `b"\n"` or carriage return `b"\r\n"`
+pub struct LineEnding;
+
+/// Prints a newline
+impl Print<u8> for LineEnding {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(Some(b'\n'));
+    }
+}
+
+impl Generate<u8> for LineEnding {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<u8>) {
+        let options: [&[u8]; 2] = [b"\n", b"\r\n"];
+        output.extend(gen.rng().choose(&options).iter().copied());
+    }
+}
+
+impl Describe<u8> for LineEnding {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        grammar.rule("LineEnding", |_| {
+            Rule::choice(vec![
+                Rule::Terminal("\n".to_string()),
+                Rule::Terminal("\r\n".to_string()),
+            ])
+        })
+    }
+}
+
+impl Parse<u8> for LineEnding {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            if Self::peek(input) {
+                Ok(Self)
+            } else {
+                Err(eyre::eyre!("could not parse line ending"))
+            }
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        match input.next() {
+            Some(b'\n') => true,
+            Some(b'\r') => input.next() == Some(b'\n'),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+/// Type that parses any ASCII space bytes (tabs, spaces)
+pub struct Space;
+
+/// Prints a single space
+impl Print<u8> for Space {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(Some(b' '));
+    }
+}
+
+impl Generate<u8> for Space {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<u8>) {
+        let options: [&[u8]; 2] = [b" ", b"\t"];
+        output.extend(gen.rng().choose(&options).iter().copied());
+    }
+}
+
+impl Describe<u8> for Space {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        grammar.rule("Space", |_| Rule::CharSet {
+            chars: " \t".to_string(),
+            negated: false,
+        })
+    }
+}
+
+impl Parse<u8> for Space {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            if Self::peek(input) {
+                Ok(Self)
+            } else {
+                Err(eyre::eyre!("could not parse space"))
+            }
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        matches!(input.next(), Some(b' ') | Some(b'\t'))
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+/// Type that parses any ASCII whitespace bytes (tabs, spaces, newlines and carriage returns)
+pub struct WhiteSpace;
+
+/// Prints a single space
+impl Print<u8> for WhiteSpace {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(Some(b' '));
+    }
+}
+
+impl Generate<u8> for WhiteSpace {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<u8>) {
+        let options: [&[u8]; 4] = [b" ", b"\t", b"\n", b"\r\n"];
+        output.extend(gen.rng().choose(&options).iter().copied());
+    }
+}
+
+impl Describe<u8> for WhiteSpace {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        grammar.rule("WhiteSpace", |_| {
+            let chars = Rule::CharSet {
+                chars: " \t\n".to_string(),
+                negated: false,
+            };
+            Rule::choice(vec![chars, Rule::Terminal("\r\n".to_string())])
+        })
+    }
+}
+
+impl Parse<u8> for WhiteSpace {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            if Self::peek(input) {
+                Ok(Self)
+            } else {
+                Err(eyre::eyre!("could not parse whitespace"))
+            }
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        match input.next() {
+            Some(b' ') | Some(b'\t') | Some(b'\n') => true,
+            Some(b'\r') => input.next() == Some(b'\n'),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntoBuf;
+
+    #[test]
+    fn parse_spaces() {
+        let mut input = b" \t \t   \t\t  \t.".to_vec().into_buf();
+        let output = Vec::<Space>::parse(&mut input).unwrap();
+        assert_eq!(output.len(), 12);
+        assert_eq!(input.next(), Some(b'.'));
+    }
+
+    #[test]
+    fn parse_whitespace() {
+        let mut input = b" \r\n\t\n\r.".to_vec().into_buf();
+        let output = Vec::<WhiteSpace>::parse(&mut input).unwrap();
+        assert_eq!(output.len(), 4);
+        assert_eq!(input.next(), Some(b'\r'));
+    }
+
+    #[test]
+    fn parse_newline() {
+        let mut input = b"\n.\r\n.".to_vec().into_buf();
+
+        let _ = LineEnding::parse(&mut input).unwrap();
+        assert_eq!(input.next(), Some(b'.'));
+        let _ = LineEnding::parse(&mut input).unwrap();
+        assert_eq!(input.next(), Some(b'.'));
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{eyre, Buffer, Parse};
+use super::set::ByteSet;
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Generate, Generator, Parse, Print,
+};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 /// `OneOf` is a generic type that implements [`Parse`] to match one character within the given string
@@ -11,17 +16,62 @@ use crate::{eyre, Buffer, Parse};
 /// ```
 pub struct OneOf<const BYTES: &'static [u8]>(u8);
 
+impl<const BYTES: &'static [u8]> OneOf<BYTES> {
+    const SET: ByteSet = ByteSet::new(BYTES);
+
+    /// Whether `b` is one of `BYTES`. The set of bytes is built at compile time,
+    /// so this doesn't have to search through `BYTES`
+    ///
+    /// ```
+    /// use nommy::bytes::OneOf;
+    /// assert!(OneOf::<b"abc">::contains(b'b'));
+    /// assert!(!OneOf::<b"abc">::contains(b'd'));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn contains(b: u8) -> bool {
+        Self::SET.contains(b)
+    }
+}
+
 impl<const BYTES: &'static [u8]> From<OneOf<BYTES>> for u8 {
     fn from(v: OneOf<BYTES>) -> Self {
         v.0
     }
 }
 
+impl<const BYTES: &'static [u8]> From<u8> for OneOf<BYTES> {
+    fn from(b: u8) -> Self {
+        Self(b)
+    }
+}
+
+impl<const BYTES: &'static [u8]> Print<u8> for OneOf<BYTES> {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(Some(self.0));
+    }
+}
+
+impl<const BYTES: &'static [u8]> Generate<u8> for OneOf<BYTES> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<u8>) {
+        output.extend(Some(*gen.rng().choose(BYTES)));
+    }
+}
+
+impl<const BYTES: &'static [u8]> Describe<u8> for OneOf<BYTES> {
+    fn describe(_: &mut Grammar) -> Rule {
+        Rule::CharSet {
+            chars: BYTES.iter().copied().map(char::from).collect(),
+            negated: false,
+        }
+    }
+}
+
 impl<const BYTES: &'static [u8]> Parse<u8> for OneOf<BYTES> {
     fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
-        match input.next() {
+        crate::context::check(input, |input| match input.next() {
             Some(c) => {
-                if BYTES.contains(&c) {
+                if Self::contains(c) {
                     Ok(Self(c))
                 } else {
                     Err(eyre::eyre!(
@@ -32,12 +82,12 @@ impl<const BYTES: &'static [u8]> Parse<u8> for OneOf<BYTES> {
                 }
             }
             None => Err(eyre::eyre!("error parsing one of {:?}, reached EOF", BYTES)),
-        }
+        })
     }
 
     fn peek(input: &mut impl Buffer<u8>) -> bool {
         match input.next() {
-            Some(c) => BYTES.contains(&c),
+            Some(c) => Self::contains(c),
             None => false,
         }
     }
@@ -0,0 +1,35 @@
+/// A set of bytes, built at compile time, which is looked up in a bitmap
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ByteSet([u128; 2]);
+
+impl ByteSet {
+    pub(crate) const fn new(bytes: &[u8]) -> Self {
+        let mut set = [0; 2];
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            set[(b >> 7) as usize] |= 1 << (b & 0x7f);
+            i += 1;
+        }
+        Self(set)
+    }
+
+    #[inline]
+    pub(crate) const fn contains(&self, b: u8) -> bool {
+        self.0[(b >> 7) as usize] & (1 << (b & 0x7f)) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains() {
+        let bytes = b"\x00az\x7f\x80\xff";
+        let set = ByteSet::new(bytes);
+        for b in 0..=255 {
+            assert_eq!(set.contains(b), bytes.contains(&b), "{}", b);
+        }
+    }
+}
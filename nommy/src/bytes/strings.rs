@@ -0,0 +1,341 @@
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    string::FromUtf8Error,
+};
+
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Parse, Print,
+};
+
+use super::{BigEndian, Leb128, LittleEndian, NativeEndian};
+
+#[derive(Debug, Clone, PartialEq)]
+/// `NulTerminated` is a type that implements [`Parse`] to read a C style string, ending with a NUL byte.
+/// The NUL byte is consumed, but not included in the output
+///
+/// ```
+/// use std::convert::TryInto;
+/// use nommy::{Parse, IntoBuf, bytes::NulTerminated};
+/// let mut buffer = b"foo\0bar".to_vec().into_buf();
+/// let s: String = NulTerminated::parse(&mut buffer).unwrap().try_into().unwrap();
+/// assert_eq!(s, "foo");
+/// assert_eq!(buffer.next(), Some(b'b'));
+/// ```
+pub struct NulTerminated(Vec<u8>);
+
+impl Parse<u8> for NulTerminated {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            let mut output = Vec::new();
+            input.read_while(|&b| b != 0, &mut output);
+            match input.next() {
+                Some(0) => Ok(Self(output)),
+                _ => Err(eyre::eyre!(
+                    "error parsing NulTerminated, reached EOF before a NUL byte"
+                )),
+            }
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        input.advance_while(|&b| b != 0);
+        input.next() == Some(0)
+    }
+}
+
+/// Prints the bytes followed by a NUL
+impl Print<u8> for NulTerminated {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(self.0.iter().copied().chain(Some(0)));
+    }
+}
+
+/// Any bytes other than NUL, followed by a NUL
+impl Describe<u8> for NulTerminated {
+    fn describe(_: &mut Grammar) -> Rule {
+        let bytes = Rule::CharSet {
+            chars: "\0".to_string(),
+            negated: true,
+        };
+        Rule::sequence(vec![
+            Rule::repeat(bytes, 0),
+            Rule::Terminal("\0".to_string()),
+        ])
+    }
+}
+
+/// Number parsers that can be used as the length prefix of a [`LengthPrefixed`]
+pub trait LengthPrefix: Parse<u8> {
+    /// Converts the parsed number into a length
+    ///
+    /// # Errors
+    /// If the number does not fit into a `usize`
+    fn length(self) -> eyre::Result<usize>;
+
+    /// Converts a length back into the number, for printing
+    ///
+    /// # Panics
+    /// If the length does not fit into the number
+    fn from_length(len: usize) -> Self;
+}
+
+fn to_length<N: TryInto<usize> + fmt::Display + Copy>(n: N) -> eyre::Result<usize> {
+    n.try_into()
+        .map_err(|_| eyre::eyre!("length prefix {} does not fit into a usize", n))
+}
+
+macro_rules! length_prefix {
+    ($prefix:ident: $($t:ty),*) => {$(
+        impl LengthPrefix for $prefix<$t> {
+            fn length(self) -> eyre::Result<usize> {
+                to_length(<$t>::from(self))
+            }
+
+            fn from_length(len: usize) -> Self {
+                let n = <$t>::try_from(len).unwrap_or_else(|_| {
+                    panic!("length {} does not fit into a {}", len, stringify!($t))
+                });
+                Self::from(n)
+            }
+        }
+    )*};
+}
+
+length_prefix!(BigEndian: u8, u16, u32, u64);
+length_prefix!(LittleEndian: u8, u16, u32, u64);
+length_prefix!(NativeEndian: u8, u16, u32, u64);
+length_prefix!(Leb128: u8, u16, u32, u64, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+/// `LengthPrefixed` is a generic type that implements [`Parse`] to read a byte array
+/// whose length is given by the [`LengthPrefix`] `L` in front of it
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::{BigEndian, LengthPrefixed}};
+/// let mut buffer = vec![0x00, 0x03, b'f', b'o', b'o', b'!'].into_buf();
+/// let s: Vec<u8> = LengthPrefixed::<BigEndian<u16>>::parse(&mut buffer).unwrap().into();
+/// assert_eq!(s, b"foo");
+/// assert_eq!(buffer.next(), Some(b'!'));
+/// ```
+pub struct LengthPrefixed<L>(Vec<u8>, std::marker::PhantomData<L>);
+
+impl<L: LengthPrefix> Parse<u8> for LengthPrefixed<L> {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            let len = L::parse(input)?.length()?;
+            let output: Vec<u8> = input.take(len).collect();
+            if output.len() < len {
+                return Err(eyre::eyre!(
+                    "error parsing LengthPrefixed, reached EOF after {} of {} bytes",
+                    output.len(),
+                    len
+                ));
+            }
+            Ok(Self(output, std::marker::PhantomData))
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        match L::parse(input).and_then(LengthPrefix::length) {
+            Ok(len) => input.take(len).count() == len,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Prints the length with `L`, followed by the bytes
+///
+/// # Panics
+/// If there are too many bytes for `L` to hold the length
+impl<L: LengthPrefix + Print<u8>> Print<u8> for LengthPrefixed<L> {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        L::from_length(self.0.len()).print(output);
+        output.extend(self.0.iter().copied());
+    }
+}
+
+impl<L: LengthPrefix + Describe<u8>> Describe<u8> for LengthPrefixed<L> {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        Rule::sequence(vec![
+            L::describe(grammar),
+            Rule::Special("that many bytes".to_string()),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// `Padded` is a generic type that implements [`Parse`] to read a fixed size field of `N` bytes,
+/// with any trailing `PAD` bytes removed
+///
+/// ```
+/// use std::convert::TryInto;
+/// use nommy::{Parse, IntoBuf, bytes::Padded};
+/// let mut buffer = b"foo\0\0\0bar".to_vec().into_buf();
+/// let s: String = Padded::<6, 0>::parse(&mut buffer).unwrap().try_into().unwrap();
+/// assert_eq!(s, "foo");
+/// assert_eq!(buffer.next(), Some(b'b'));
+/// ```
+pub struct Padded<const N: usize, const PAD: u8>(Vec<u8>);
+
+impl<const N: usize, const PAD: u8> Parse<u8> for Padded<N, PAD> {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            let mut output: Vec<u8> = input.take(N).collect();
+            if output.len() < N {
+                return Err(eyre::eyre!(
+                    "error parsing Padded, reached EOF after {} of {} bytes",
+                    output.len(),
+                    N
+                ));
+            }
+            while output.last() == Some(&PAD) {
+                output.pop();
+            }
+            Ok(Self(output))
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        input.take(N).count() == N
+    }
+}
+
+/// Prints the bytes, followed by as many `PAD` bytes as it takes to fill `N`
+impl<const N: usize, const PAD: u8> Print<u8> for Padded<N, PAD> {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        let padding = N.saturating_sub(self.0.len());
+        output.extend(
+            self.0
+                .iter()
+                .copied()
+                .chain(std::iter::repeat(PAD).take(padding)),
+        );
+    }
+}
+
+impl<const N: usize, const PAD: u8> Describe<u8> for Padded<N, PAD> {
+    fn describe(_: &mut Grammar) -> Rule {
+        Rule::Special(format!("{} bytes padded with {:#04x}", N, PAD))
+    }
+}
+
+macro_rules! byte_string {
+    ($($ty:ty => [$($generics:tt)*] ($($extra:expr),*)),*) => {$(
+        impl<$($generics)*> From<$ty> for Vec<u8> {
+            fn from(v: $ty) -> Self {
+                v.0
+            }
+        }
+
+        impl<$($generics)*> TryFrom<$ty> for String {
+            type Error = FromUtf8Error;
+            fn try_from(v: $ty) -> Result<Self, Self::Error> {
+                String::from_utf8(v.0)
+            }
+        }
+
+        impl<$($generics)*> From<Vec<u8>> for $ty {
+            fn from(bytes: Vec<u8>) -> Self {
+                Self(bytes $(, $extra)*)
+            }
+        }
+
+        impl<$($generics)*> From<String> for $ty {
+            fn from(s: String) -> Self {
+                Self::from(s.into_bytes())
+            }
+        }
+    )*};
+}
+
+byte_string! {
+    NulTerminated => [] (),
+    LengthPrefixed<L> => [L] (std::marker::PhantomData),
+    Padded<N, PAD> => [const N: usize, const PAD: u8] ()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, print, IntoBuf};
+
+    #[test]
+    fn nul_terminated() {
+        let mut input = b"foo\0\0bar".to_vec().into_buf();
+        let s: Vec<u8> = NulTerminated::parse(&mut input).unwrap().into();
+        assert_eq!(s, b"foo");
+        let s: Vec<u8> = NulTerminated::parse(&mut input).unwrap().into();
+        assert_eq!(s, b"");
+
+        assert!(!NulTerminated::peek(&mut input.cursor()));
+        assert!(NulTerminated::parse(&mut input).is_err());
+    }
+
+    #[test]
+    fn length_prefixed() {
+        let s: Vec<u8> = parse::<LengthPrefixed<BigEndian<u8>>, _>(b"\x03foo".to_vec())
+            .unwrap()
+            .into();
+        assert_eq!(s, b"foo");
+
+        let s: Vec<u8> = parse::<LengthPrefixed<LittleEndian<u32>>, _>(b"\x02\0\0\0hi".to_vec())
+            .unwrap()
+            .into();
+        assert_eq!(s, b"hi");
+
+        let s: Vec<u8> = parse::<LengthPrefixed<Leb128<usize>>, _>(b"\x01a".to_vec())
+            .unwrap()
+            .into();
+        assert_eq!(s, b"a");
+    }
+
+    #[test]
+    fn length_prefixed_eof() {
+        let mut input = b"\x05foo".to_vec().into_buf();
+        assert!(!LengthPrefixed::<BigEndian<u8>>::peek(&mut input.cursor()));
+        let err = LengthPrefixed::<BigEndian<u8>>::parse(&mut input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "error parsing LengthPrefixed, reached EOF after 3 of 5 bytes"
+        );
+    }
+
+    #[test]
+    fn padded() {
+        let s: Vec<u8> = parse::<Padded<5, b' '>, _>(b"ab c  ".to_vec())
+            .unwrap()
+            .into();
+        assert_eq!(s, b"ab c");
+        assert!(parse::<Padded<5, 0>, _>(b"abc".to_vec()).is_err());
+    }
+
+    #[test]
+    fn print_strings() {
+        let bytes: Vec<u8> = print(&NulTerminated::from("foo".to_string()));
+        assert_eq!(bytes, b"foo\0");
+        let bytes: Vec<u8> = print(&LengthPrefixed::<BigEndian<u16>>::from(b"hi".to_vec()));
+        assert_eq!(bytes, b"\0\x02hi");
+        let bytes: Vec<u8> = print(&LengthPrefixed::<Leb128<usize>>::from(vec![b'a'; 200]));
+        assert_eq!(bytes[..2], [0xc8, 0x01]);
+        assert_eq!(bytes.len(), 202);
+        let bytes: Vec<u8> = print(&Padded::<5, b' '>::from(b"ab".to_vec()));
+        assert_eq!(bytes, b"ab   ");
+    }
+
+    #[test]
+    #[should_panic(expected = "length 256 does not fit into a u8")]
+    fn print_length_overflow() {
+        let _: Vec<u8> = print(&LengthPrefixed::<BigEndian<u8>>::from(vec![0; 256]));
+    }
+
+    #[test]
+    fn utf8() {
+        let s: Result<String, _> = parse::<NulTerminated, _>(b"\xff\xfe\0".to_vec())
+            .unwrap()
+            .try_into();
+        assert!(s.is_err());
+    }
+}
@@ -1,6 +1,12 @@
-use crate::{eyre, Buffer, Parse};
+use std::{error::Error, fmt};
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Generate, Generator, Parse, Print,
+};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// `Tag` is a generic type that implements [`Parse`] to match the given string exactly
 ///
 /// ```
@@ -11,18 +17,78 @@ use crate::{eyre, Buffer, Parse};
 /// ```
 pub struct Tag<const TAG: &'static [u8]>;
 
+impl<const TAG: &'static [u8]> Print<u8> for Tag<TAG> {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(TAG.iter().copied());
+    }
+}
+
+impl<const TAG: &'static [u8]> Generate<u8> for Tag<TAG> {
+    fn generate(_: &mut Generator, output: &mut impl Extend<u8>) {
+        output.extend(TAG.iter().copied());
+    }
+}
+
+impl<const TAG: &'static [u8]> Describe<u8> for Tag<TAG> {
+    fn describe(_: &mut Grammar) -> Rule {
+        Rule::Terminal(TAG.iter().copied().map(char::from).collect())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Error returned when a [`Tag`] doesn't match.
+/// The bytes that were found instead are only collected when the error is displayed
+///
+/// ```
+/// use nommy::{parse, bytes::{Tag, TagError}};
+/// let res: Result<Tag<b"GET">, _> = parse(b"GOT".iter().copied());
+/// let err = res.unwrap_err();
+/// assert_eq!(err.downcast_ref(), Some(&TagError { tag: b"GET", matched: 1, found: Some(b'O') }));
+/// assert_eq!(err.to_string(), "failed to parse tag [71, 69, 84], found [71, 79]");
+/// ```
+pub struct TagError {
+    /// The tag that was expected
+    pub tag: &'static [u8],
+    /// How many bytes of the tag matched
+    pub matched: usize,
+    /// The byte that didn't match, or `None` if the input ended
+    pub found: Option<u8>,
+}
+
+impl Error for TagError {}
+impl fmt::Display for TagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let found: Vec<u8> = self.tag[..self.matched]
+            .iter()
+            .copied()
+            .chain(self.found)
+            .collect();
+        write!(f, "failed to parse tag {:?}, found {:?}", self.tag, found)
+    }
+}
+
 impl<const TAG: &'static [u8]> Parse<u8> for Tag<TAG> {
     fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
-        let b: Vec<u8> = input.take(TAG.len()).collect();
-        if TAG == b {
+        crate::context::check(input, |input| {
+            for (matched, &b) in TAG.iter().enumerate() {
+                match input.next() {
+                    Some(found) if found == b => {}
+                    found => {
+                        return Err(TagError {
+                            tag: TAG,
+                            matched,
+                            found,
+                        }
+                        .into())
+                    }
+                }
+            }
             Ok(Self)
-        } else {
-            Err(eyre::eyre!("failed to parse tag {:?}, found {:?}", TAG, b))
-        }
+        })
     }
 
     fn peek(input: &mut impl Buffer<u8>) -> bool {
-        TAG.iter().cloned().eq(input.take(TAG.len()))
+        TAG.iter().all(|&b| input.next() == Some(b))
     }
 }
 
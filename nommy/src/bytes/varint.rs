@@ -0,0 +1,397 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Parse, Print,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// `Leb128` is a generic type that implements [`Parse`] to read a LEB128 encoded variable length integer.
+/// Unsigned integer types read unsigned LEB128, signed integer types read signed LEB128.
+///
+/// Errors with a [`VarIntError`] if the encoding is overlong or the value overflows `T`
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::Leb128};
+/// let mut buffer = vec![0xe5, 0x8e, 0x26, 0xc0, 0xbb, 0x78].into_buf();
+/// let n: u32 = Leb128::<u32>::parse(&mut buffer).unwrap().into();
+/// assert_eq!(n, 624_485);
+/// let n: i32 = Leb128::<i32>::parse(&mut buffer).unwrap().into();
+/// assert_eq!(n, -123_456);
+/// ```
+pub struct Leb128<T>(T);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// `ZigZag` is a generic type that implements [`Parse`] to read a zigzag encoded signed integer,
+/// stored as an unsigned LEB128 varint (as used by protobuf's `sint` types)
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, bytes::ZigZag};
+/// let mut buffer = vec![0x03, 0x04].into_buf();
+/// let n: i64 = ZigZag::<i64>::parse(&mut buffer).unwrap().into();
+/// assert_eq!(n, -2);
+/// let n: i64 = ZigZag::<i64>::parse(&mut buffer).unwrap().into();
+/// assert_eq!(n, 2);
+/// ```
+pub struct ZigZag<T>(T);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Error returned when a variable length integer could not be decoded
+///
+/// ```
+/// use nommy::{parse, bytes::{Leb128, VarIntError}};
+/// let res: Result<Leb128<u8>, _> = parse(vec![0x80, 0x02]);
+/// let err = res.unwrap_err();
+/// assert_eq!(err.downcast_ref(), Some(&VarIntError::Overflow("u8")));
+/// ```
+pub enum VarIntError {
+    /// The input ended before the last byte of the integer
+    Eof,
+    /// The integer was encoded with more bytes than necessary
+    Overlong,
+    /// The integer does not fit into the given type
+    Overflow(&'static str),
+}
+
+impl Error for VarIntError {}
+impl fmt::Display for VarIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eof => write!(f, "error parsing varint, reached EOF"),
+            Self::Overlong => write!(f, "error parsing varint, encoding is overlong"),
+            Self::Overflow(ty) => write!(f, "error parsing varint, value overflows {}", ty),
+        }
+    }
+}
+
+/// Reads an unsigned LEB128 integer that must fit within `bits` bits
+fn read_unsigned(
+    input: &mut impl Buffer<u8>,
+    bits: u32,
+    name: &'static str,
+) -> Result<u128, VarIntError> {
+    let mut result = 0_u128;
+    let mut shift = 0_u32;
+    loop {
+        let byte = input.next().ok_or(VarIntError::Eof)?;
+        let payload = u128::from(byte & 0x7f);
+
+        if shift >= bits {
+            // the byte is entirely past the width of the type, so it can only be padding
+            return Err(if payload == 0 {
+                VarIntError::Overlong
+            } else {
+                VarIntError::Overflow(name)
+            });
+        }
+        if shift + 7 > bits && payload >> (bits - shift) != 0 {
+            return Err(VarIntError::Overflow(name));
+        }
+        result |= payload << shift;
+        shift = shift.saturating_add(7);
+
+        if byte & 0x80 == 0 {
+            if byte == 0 && shift > 7 {
+                return Err(VarIntError::Overlong);
+            }
+            return Ok(result);
+        }
+    }
+}
+
+/// Reads a signed LEB128 integer that must fit within `bits` bits.
+/// The result is sign extended to the full width of the `u128`
+fn read_signed(
+    input: &mut impl Buffer<u8>,
+    bits: u32,
+    name: &'static str,
+) -> Result<u128, VarIntError> {
+    let mut result = 0_u128;
+    let mut shift = 0_u32;
+    let mut prev = 0_u8;
+    loop {
+        let byte = input.next().ok_or(VarIntError::Eof)?;
+        let payload = byte & 0x7f;
+
+        if shift >= bits {
+            // the byte is entirely past the width of the type, so it can only be padding,
+            // with every bit matching the sign bit
+            let negative = result >> (bits - 1) & 1 == 1;
+            return Err(if payload == if negative { 0x7f } else { 0 } {
+                VarIntError::Overlong
+            } else {
+                VarIntError::Overflow(name)
+            });
+        }
+        if shift + 7 > bits {
+            // the sign bit of the type and every bit above it must be equal
+            let high = payload >> (bits - 1 - shift);
+            if high != 0 && u32::from(high) != (1 << (7 - (bits - 1 - shift))) - 1 {
+                return Err(VarIntError::Overflow(name));
+            }
+        }
+        result |= u128::from(payload) << shift;
+        shift = shift.saturating_add(7);
+
+        if byte & 0x80 == 0 {
+            let prev_negative = prev & 0x40 != 0;
+            if shift > 7 && ((byte == 0 && !prev_negative) || (byte == 0x7f && prev_negative)) {
+                return Err(VarIntError::Overlong);
+            }
+            if shift < 128 && payload & 0x40 != 0 {
+                result |= !0 << shift;
+            }
+            return Ok(result);
+        }
+        prev = byte;
+    }
+}
+
+/// Writes `n` as an unsigned LEB128 integer, using as few bytes as possible
+#[allow(clippy::cast_possible_truncation)]
+fn write_unsigned(mut n: u128, output: &mut impl Extend<u8>) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            output.extend(Some(byte));
+            return;
+        }
+        output.extend(Some(byte | 0x80));
+    }
+}
+
+/// Writes `n` as a signed LEB128 integer, using as few bytes as possible
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn write_signed(mut n: i128, output: &mut impl Extend<u8>) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        // done once the rest of the bits match the sign bit of this byte
+        if (n == 0 && byte & 0x40 == 0) || (n == -1 && byte & 0x40 != 0) {
+            output.extend(Some(byte));
+            return;
+        }
+        output.extend(Some(byte | 0x80));
+    }
+}
+
+macro_rules! leb128 {
+    ($read:ident, $write:ident: $($t:ty),*) => {$(
+        impl Parse<u8> for Leb128<$t> {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+                crate::context::check(input, |input| {
+                    let n = $read(input, <$t>::BITS, stringify!($t))?;
+                    Ok(Self(n as $t))
+                })
+            }
+        }
+
+        impl From<Leb128<$t>> for $t {
+            fn from(v: Leb128<$t>) -> Self {
+                v.0
+            }
+        }
+
+        impl From<$t> for Leb128<$t> {
+            fn from(n: $t) -> Self {
+                Self(n)
+            }
+        }
+
+        impl Print<u8> for Leb128<$t> {
+            #[allow(clippy::cast_lossless)]
+            fn print(&self, output: &mut impl Extend<u8>) {
+                $write(self.0 as _, output);
+            }
+        }
+
+        impl Describe<u8> for Leb128<$t> {
+            fn describe(_: &mut Grammar) -> Rule {
+                Rule::Special(concat!("Leb128<", stringify!($t), ">").to_string())
+            }
+        }
+    )*};
+}
+
+leb128!(read_unsigned, write_unsigned: u8, u16, u32, u64, u128, usize);
+leb128!(read_signed, write_signed: i8, i16, i32, i64, i128, isize);
+
+macro_rules! zigzag {
+    ($($t:ty),*) => {$(
+        impl Parse<u8> for ZigZag<$t> {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+                crate::context::check(input, |input| {
+                    let n = read_unsigned(input, <$t>::BITS, stringify!($t))?;
+                    let n = (n >> 1) as $t ^ -((n & 1) as $t);
+                    Ok(Self(n))
+                })
+            }
+        }
+
+        impl From<ZigZag<$t>> for $t {
+            fn from(v: ZigZag<$t>) -> Self {
+                v.0
+            }
+        }
+
+        impl From<$t> for ZigZag<$t> {
+            fn from(n: $t) -> Self {
+                Self(n)
+            }
+        }
+
+        impl Print<u8> for ZigZag<$t> {
+            #[allow(clippy::cast_lossless, clippy::cast_sign_loss)]
+            fn print(&self, output: &mut impl Extend<u8>) {
+                let n = self.0 as i128;
+                write_unsigned(((n << 1) ^ (n >> 127)) as u128, output);
+            }
+        }
+
+        impl Describe<u8> for ZigZag<$t> {
+            fn describe(_: &mut Grammar) -> Rule {
+                Rule::Special(concat!("ZigZag<", stringify!($t), ">").to_string())
+            }
+        }
+    )*};
+}
+
+zigzag!(i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, print, SliceBuf};
+
+    fn err<P: Parse<u8>>(input: &[u8]) -> VarIntError {
+        let err = parse::<P, _>(input.to_vec()).err().unwrap();
+        *err.downcast_ref::<VarIntError>().unwrap()
+    }
+
+    #[test]
+    fn unsigned() {
+        let n: u64 = parse::<Leb128<u64>, _>(vec![0x00]).unwrap().into();
+        assert_eq!(n, 0);
+        let n: u64 = parse::<Leb128<u64>, _>(vec![0x7f]).unwrap().into();
+        assert_eq!(n, 127);
+        let n: u64 = parse::<Leb128<u64>, _>(vec![0x80, 0x01]).unwrap().into();
+        assert_eq!(n, 128);
+        let n: u8 = parse::<Leb128<u8>, _>(vec![0xff, 0x01]).unwrap().into();
+        assert_eq!(n, 255);
+        let mut max = vec![0xff; 18];
+        max.push(0x03);
+        let n: u128 = parse::<Leb128<u128>, _>(max).unwrap().into();
+        assert_eq!(n, u128::MAX);
+    }
+
+    #[test]
+    fn signed() {
+        let n: i32 = parse::<Leb128<i32>, _>(vec![0x7f]).unwrap().into();
+        assert_eq!(n, -1);
+        let n: i32 = parse::<Leb128<i32>, _>(vec![0xff, 0x00]).unwrap().into();
+        assert_eq!(n, 127);
+        let n: i8 = parse::<Leb128<i8>, _>(vec![0x80, 0x7f]).unwrap().into();
+        assert_eq!(n, i8::MIN);
+        let n: i8 = parse::<Leb128<i8>, _>(vec![0xff, 0x00]).unwrap().into();
+        assert_eq!(n, i8::MAX);
+        let n: i64 = parse::<Leb128<i64>, _>(vec![0x80, 0x80, 0x80, 0x80, 0x78])
+            .unwrap()
+            .into();
+        assert_eq!(n, -(1 << 31));
+    }
+
+    #[test]
+    fn zigzag() {
+        for (bytes, expected) in &[(0x00, 0), (0x01, -1), (0x02, 1), (0x7f, -64)] {
+            let n: i32 = parse::<ZigZag<i32>, _>(vec![*bytes]).unwrap().into();
+            assert_eq!(n, *expected);
+        }
+        let n: i8 = parse::<ZigZag<i8>, _>(vec![0xff, 0x01]).unwrap().into();
+        assert_eq!(n, i8::MIN);
+    }
+
+    #[test]
+    fn print_round_trip() {
+        fn round_trip<P: Parse<u8> + Print<u8>>(p: &P) -> Vec<u8> {
+            let bytes: Vec<u8> = print(p);
+            let q: P = parse(bytes.clone()).unwrap();
+            assert_eq!(print::<u8, _, Vec<u8>>(&q), bytes);
+            bytes
+        }
+
+        assert_eq!(round_trip(&Leb128::from(624_485_u32)), [0xe5, 0x8e, 0x26]);
+        assert_eq!(round_trip(&Leb128::from(-123_456_i32)), [0xc0, 0xbb, 0x78]);
+        assert_eq!(round_trip(&Leb128::from(0_u8)), [0x00]);
+        assert_eq!(round_trip(&Leb128::from(u128::MAX)).len(), 19);
+        assert_eq!(round_trip(&Leb128::from(i8::MIN)), [0x80, 0x7f]);
+        assert_eq!(round_trip(&Leb128::from(i8::MAX)), [0xff, 0x00]);
+        assert_eq!(round_trip(&Leb128::from(-1_i64)), [0x7f]);
+        assert_eq!(round_trip(&ZigZag::from(-2_i64)), [0x03]);
+        assert_eq!(round_trip(&ZigZag::from(i8::MIN)), [0xff, 0x01]);
+        assert_eq!(round_trip(&ZigZag::from(i128::MIN)).len(), 19);
+    }
+
+    #[test]
+    fn overlong() {
+        assert_eq!(err::<Leb128<u32>>(&[0x80, 0x00]), VarIntError::Overlong);
+        assert_eq!(
+            err::<Leb128<u32>>(&[0x81, 0x80, 0x00]),
+            VarIntError::Overlong
+        );
+        assert_eq!(err::<Leb128<i32>>(&[0xff, 0x7f]), VarIntError::Overlong);
+        assert_eq!(err::<Leb128<i32>>(&[0x80, 0x00]), VarIntError::Overlong);
+        assert_eq!(err::<ZigZag<i32>>(&[0x81, 0x00]), VarIntError::Overlong);
+
+        // padding stops being read once it's past the width of the type
+        let mut input = SliceBuf::new(&[0x80; 100]);
+        assert_eq!(
+            Leb128::<u8>::parse(&mut input).unwrap_err().downcast_ref(),
+            Some(&VarIntError::Overlong)
+        );
+        assert_eq!(input.remaining().len(), 97);
+        let mut input = SliceBuf::new(&[0xff; 100]);
+        assert_eq!(
+            Leb128::<i32>::parse(&mut input).unwrap_err().downcast_ref(),
+            Some(&VarIntError::Overlong)
+        );
+        assert_eq!(input.remaining().len(), 94);
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(
+            err::<Leb128<u8>>(&[0x80, 0x02]),
+            VarIntError::Overflow("u8")
+        );
+        assert_eq!(
+            err::<Leb128<u32>>(&[0xff, 0xff, 0xff, 0xff, 0x1f]),
+            VarIntError::Overflow("u32")
+        );
+        assert_eq!(
+            err::<Leb128<u32>>(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x01]),
+            VarIntError::Overflow("u32")
+        );
+        assert_eq!(
+            err::<Leb128<i8>>(&[0x80, 0x01]),
+            VarIntError::Overflow("i8")
+        );
+        assert_eq!(
+            err::<Leb128<i8>>(&[0xff, 0x7e]),
+            VarIntError::Overflow("i8")
+        );
+        assert_eq!(
+            err::<ZigZag<i8>>(&[0x80, 0x02]),
+            VarIntError::Overflow("i8")
+        );
+    }
+
+    #[test]
+    fn eof() {
+        assert_eq!(err::<Leb128<u32>>(&[0x80]), VarIntError::Eof);
+        assert_eq!(err::<Leb128<i32>>(&[]), VarIntError::Eof);
+    }
+}
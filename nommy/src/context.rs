@@ -0,0 +1,450 @@
+//! Limits on how much a single parse can do, so that untrusted input can't exhaust the stack or run for too long.
+//!
+//! Every buffer in nommy carries a [`Context`], available through [`Buffer::context`].
+//! A type can only contain itself through a [`Box`] or a [`Vec`], so each [`Box`], each [`Vec`] or [`Vec1`](crate::Vec1)
+//! field of a derived type, and each [`pratt`](crate::pratt) operator counts as one level of nesting.
+//! They fail with [`DepthExceeded`] once they go deeper than [`max_depth`](Context::max_depth) allows
+//!
+//! ```
+//! use nommy::{context::{Context, DepthExceeded}, text::Tag, Buf, Parse};
+//!
+//! #[derive(Debug, Parse)]
+//! #[nommy(parse_type = char)]
+//! enum List {
+//!     #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+//!     Nested(Box<List>),
+//!     #[nommy(prefix = Tag<"x">)]
+//!     Item,
+//! }
+//!
+//! let mut input = Buf::new("[[[x]]]".chars()).with_context(Context::new().max_depth(8));
+//! List::parse(&mut input).unwrap();
+//!
+//! let mut input = Buf::new("[[[x]]]".chars()).with_context(Context::new().max_depth(2));
+//! let err = List::parse(&mut input).unwrap_err();
+//! assert_eq!(err.downcast_ref(), Some(&DepthExceeded { max_depth: 2 }));
+//! ```
+//!
+//! A context can also be given a [`budget`](Context::budget) of reads, and a [`cancel_flag`](Context::cancel_flag)
+//! that another thread can set. Once either runs out, the buffer reads as if the input has ended,
+//! and derived and built in parsers fail with [`Interrupted`]
+//!
+//! ```
+//! use nommy::{context::{Context, Interrupted}, text::AnyOf1, Buf, Parse};
+//!
+//! #[derive(Debug, Parse)]
+//! struct Word(#[nommy(parser = AnyOf1<"ab">)] String);
+//!
+//! let mut input = Buf::new("abab".chars()).with_context(Context::new().budget(3));
+//! let err = Word::parse(&mut input).unwrap_err();
+//! assert_eq!(err.downcast_ref(), Some(&Interrupted::BudgetExhausted { budget: 3 }));
+//! ```
+
+use std::{
+    error::Error,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::{eyre, Buffer};
+
+/// The depth that a [`Context`] allows by default.
+///
+/// Parsing used to have no depth limit, so input that's nested deeper than this, and used to parse, now fails
+/// with [`DepthExceeded`] unless the limit is raised with [`Context::max_depth`]
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// The state of a parse that's shared by all the cursors of a buffer
+#[derive(Debug, Clone)]
+pub struct Context {
+    depth: usize,
+    max_depth: usize,
+    /// The max depth was reached since the outermost [`check`] started
+    exceeded: bool,
+    /// How many [`check`]s the parse is currently inside of
+    checks: usize,
+    steps: usize,
+    budget: Option<usize>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    interrupted: Option<Interrupted>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Context {
+    /// Creates a new Context with a max depth of [`DEFAULT_MAX_DEPTH`], and no budget
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            exceeded: false,
+            checks: 0,
+            steps: 0,
+            budget: None,
+            cancel_flag: None,
+            interrupted: None,
+        }
+    }
+
+    /// Sets how many levels values can nest before parsing fails
+    #[must_use]
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets how many reads the buffer can make before parsing fails.
+    /// Reads made by cursors count too, so backtracking over the same input uses up more of the budget
+    #[must_use]
+    pub const fn budget(mut self, budget: usize) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Sets a flag that stops the parse once it's set, so it can be cancelled from another thread
+    #[must_use]
+    pub fn cancel_flag(mut self, cancel_flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(cancel_flag);
+        self
+    }
+
+    /// How many levels deep the parse currently is
+    #[must_use]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// How many reads the buffer has made
+    #[must_use]
+    pub const fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Why the parse was stopped, if it was
+    #[must_use]
+    pub const fn interrupted(&self) -> Option<Interrupted> {
+        self.interrupted
+    }
+
+    /// Counts a read, returning false if the buffer should act as if the input has ended instead.
+    /// [`Buffer`] implementations call this before every read
+    pub fn step(&mut self) -> bool {
+        if self.interrupted.is_some() {
+            return false;
+        }
+        if let Some(budget) = self.budget {
+            if self.steps >= budget {
+                self.interrupted = Some(Interrupted::BudgetExhausted { budget });
+                return false;
+            }
+        }
+        if let Some(cancel_flag) = &self.cancel_flag {
+            if cancel_flag.load(Ordering::Relaxed) {
+                self.interrupted = Some(Interrupted::Cancelled);
+                return false;
+            }
+        }
+        self.steps += 1;
+        true
+    }
+
+    /// Goes one level deeper, unless that would go past the max depth
+    fn enter(&mut self) -> Result<(), DepthExceeded> {
+        if self.depth >= self.max_depth {
+            self.exceeded = true;
+            return Err(self.depth_exceeded());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Comes back up a level
+    fn exit(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Leaves a [`check`], returning the error to report if the parse was stopped while inside it.
+    /// Reaching the max depth is only forgotten once the outermost check is left, so the context can be reused
+    fn end_check(&mut self) -> eyre::Result<()> {
+        self.checks -= 1;
+        let result = if let Some(interrupted) = self.interrupted {
+            Err(interrupted.into())
+        } else if self.exceeded {
+            Err(self.depth_exceeded().into())
+        } else {
+            Ok(())
+        };
+        if self.checks == 0 {
+            self.exceeded = false;
+        }
+        result
+    }
+
+    /// Whether the parse has been interrupted, or has reached the max depth since the outermost check started.
+    /// Results from then on depend on more than just the input, so they can't be memoized
+    pub(crate) const fn limited(&self) -> bool {
+        self.exceeded || self.interrupted.is_some()
+    }
+
+    const fn depth_exceeded(&self) -> DepthExceeded {
+        DepthExceeded {
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+/// The error returned when values nest deeper than the [`Context`] allows
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DepthExceeded {
+    /// The max depth of the context
+    pub max_depth: usize,
+}
+
+impl Error for DepthExceeded {}
+impl fmt::Display for DepthExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "input is nested more than {} levels deep",
+            self.max_depth
+        )
+    }
+}
+
+/// The error returned once a [`Context`] has stopped the parse
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Interrupted {
+    /// The buffer made more reads than the budget allows
+    BudgetExhausted {
+        /// The budget of the context
+        budget: usize,
+    },
+    /// The cancel flag was set
+    Cancelled,
+}
+
+impl Error for Interrupted {}
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BudgetExhausted { budget } => {
+                write!(f, "parse ran out of its budget of {} reads", budget)
+            }
+            Self::Cancelled => write!(f, "parse was cancelled"),
+        }
+    }
+}
+
+/// Parses using `f`, failing with [`Interrupted`] if the context stopped the parse along the way,
+/// or with [`DepthExceeded`] if the max depth was reached anywhere inside it.
+///
+/// This fails even if `f` succeeded, since it could have only seen part of the input,
+/// or an enum or a `Vec` could have fallen back to parsing less of it.
+/// Used by [`parse`], derived types and the built in parsers, and hand written parsers can call it the same way
+///
+/// # Errors
+/// If the parse was interrupted or reached the max depth, or `f` fails
+pub fn check<P, T, B: Buffer<T>>(
+    input: &mut B,
+    f: impl FnOnce(&mut B) -> eyre::Result<P>,
+) -> eyre::Result<P> {
+    match input.context() {
+        Some(context) => context.checks += 1,
+        None => return f(input),
+    }
+    let result = f(input);
+    match input.context().map(Context::end_check) {
+        Some(Err(err)) => Err(err),
+        _ => result,
+    }
+}
+
+/// Peeks using `f`, returning false if the context stopped the parse along the way, or the max depth was reached.
+///
+/// Used by [`peek`], derived types and the built in parsers that can match at the end of the input, such as `Vec`
+pub fn check_peek<T, B: Buffer<T>>(input: &mut B, f: impl FnOnce(&mut B) -> bool) -> bool {
+    match input.context() {
+        Some(context) => context.checks += 1,
+        None => return f(input),
+    }
+    let result = f(input);
+    let stopped = input
+        .context()
+        .map_or(false, |context| context.end_check().is_err());
+    result && !stopped
+}
+
+/// Parses one level deeper using `f`. Used by [`Box`], the vec fields of derived types and [`pratt`](crate::pratt) operators,
+/// and hand written parsers should call it wherever they recurse.
+///
+/// Once the max depth has been reached anywhere below, this fails with [`DepthExceeded`] even if `f` succeeded,
+/// and so does every [`check`] around it
+///
+/// # Errors
+/// If the max depth has been reached, the parse was [interrupted](check), or `f` fails
+pub fn parse<P, T, B: Buffer<T>>(
+    input: &mut B,
+    f: impl FnOnce(&mut B) -> eyre::Result<P>,
+) -> eyre::Result<P> {
+    check(input, |input| {
+        match input.context().map(Context::enter) {
+            Some(Err(exceeded)) => return Err(exceeded.into()),
+            Some(Ok(())) => {}
+            None => return f(input),
+        }
+        let result = f(input);
+        input.context().map(Context::exit);
+        result
+    })
+}
+
+/// Peeks one level deeper using `f`, returning false once the max depth has been reached anywhere below,
+/// or if the parse was [interrupted](check_peek)
+pub fn peek<T, B: Buffer<T>>(input: &mut B, f: impl FnOnce(&mut B) -> bool) -> bool {
+    check_peek(input, |input| {
+        match input.context().map(Context::enter) {
+            Some(Err(_)) => return false,
+            Some(Ok(())) => {}
+            None => return f(input),
+        }
+        let result = f(input);
+        input.context().map(Context::exit);
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        text::{AnyOf1, Tag},
+        Buf, Parse, SliceBuf,
+    };
+
+    /// Any number of `(` followed by the same number of `)`
+    #[derive(Debug, PartialEq)]
+    struct Parens(usize);
+
+    impl Parse<char> for Parens {
+        fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+            if !Tag::<"(">::peek(&mut input.cursor()) {
+                return Ok(Self(0));
+            }
+            parse(input, |input| {
+                Tag::<"(">::parse(input)?;
+                let inner = Self::parse(input)?;
+                Tag::<")">::parse(input)?;
+                Ok(Self(inner.0 + 1))
+            })
+        }
+
+        fn peek(input: &mut impl Buffer<char>) -> bool {
+            if !Tag::<"(">::peek(&mut input.cursor()) {
+                return true;
+            }
+            peek(input, |input| {
+                Tag::<"(">::peek(input) && Self::peek(input) && Tag::<")">::peek(input)
+            })
+        }
+    }
+
+    fn parens(depth: usize) -> impl Iterator<Item = char> {
+        std::iter::repeat('(')
+            .take(depth)
+            .chain(std::iter::repeat(')').take(depth))
+    }
+
+    #[test]
+    fn within_max_depth() {
+        let mut input = Buf::new(parens(4)).with_context(Context::new().max_depth(4));
+        assert!(Parens::peek(&mut input.cursor()));
+        assert_eq!(Parens::parse(&mut input).unwrap(), Parens(4));
+        assert_eq!(input.context().unwrap().depth(), 0);
+    }
+
+    #[test]
+    fn past_max_depth() {
+        let mut input = Buf::new(parens(5)).with_context(Context::new().max_depth(4));
+        assert!(!Parens::peek(&mut input.cursor()));
+        let err = Parens::parse(&mut input).unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&DepthExceeded { max_depth: 4 }));
+        assert_eq!(err.to_string(), "input is nested more than 4 levels deep");
+
+        // the context can be reused after a failure
+        let context = input.context().unwrap();
+        assert_eq!(context.depth(), 0);
+        assert!(!context.exceeded);
+    }
+
+    #[test]
+    fn default_max_depth() {
+        let mut input = Buf::new(parens(100_000));
+        let err = Parens::parse(&mut input).unwrap_err();
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&DepthExceeded {
+                max_depth: DEFAULT_MAX_DEPTH
+            })
+        );
+    }
+
+    #[test]
+    fn budget() {
+        let mut input = Buf::new(parens(4)).with_context(Context::new().budget(1000));
+        Parens::parse(&mut input).unwrap();
+        let steps = input.context().unwrap().steps();
+
+        let mut input = Buf::new(parens(4)).with_context(Context::new().budget(steps - 1));
+        let err = Parens::parse(&mut input).unwrap_err();
+        let exhausted = Interrupted::BudgetExhausted { budget: steps - 1 };
+        assert_eq!(err.downcast_ref(), Some(&exhausted));
+        assert_eq!(input.context().unwrap().interrupted(), Some(exhausted));
+        assert_eq!(input.next(), None);
+    }
+
+    #[test]
+    fn interrupted_success() {
+        // reading stops early, so the parse only saw part of the input
+        let mut input = SliceBuf::new(b"abc").with_context(Context::new().budget(2));
+        let err = check(&mut input, |input| Ok(input.by_ref().collect::<Vec<u8>>())).unwrap_err();
+        assert_eq!(err.to_string(), "parse ran out of its budget of 2 reads");
+        assert_eq!(input.context().unwrap().steps(), 2);
+    }
+
+    #[test]
+    fn cancelled() {
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let context = Context::new().cancel_flag(Arc::clone(&cancel_flag));
+        let mut input = Buf::new(parens(4)).with_context(context);
+        assert_eq!(input.next(), Some('('));
+
+        cancel_flag.store(true, Ordering::Relaxed);
+        let err = check(&mut input, Parens::parse).unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&Interrupted::Cancelled));
+    }
+
+    #[test]
+    fn built_in_parsers() {
+        // these would otherwise match the part of the input read before the budget ran out
+        let context = Context::new().budget(3);
+        let mut input = Buf::new("aaaaa".chars()).with_context(context.clone());
+        let err = AnyOf1::<"a">::parse(&mut input).unwrap_err();
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&Interrupted::BudgetExhausted { budget: 3 })
+        );
+
+        let mut input = Buf::new("aaaaa".chars()).with_context(context);
+        assert!(!Vec::<Tag<"a">>::peek(&mut input.cursor()));
+        let err = Vec::<Tag<"a">>::parse(&mut input).unwrap_err();
+        assert!(err.is::<Interrupted>());
+    }
+}
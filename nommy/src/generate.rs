@@ -0,0 +1,220 @@
+use crate::{testing::Rng, Vec1};
+
+/// Produces random input that `Self` would [parse](crate::Parse). Useful for fuzzing the code that consumes a grammar
+///
+/// ```
+/// use nommy::{generate, parse_terminated, text::{AnyOf1, Tag}, Generate, Generator, Parse};
+///
+/// #[derive(Debug, Parse, Generate)]
+/// struct Call {
+///     #[nommy(parser = AnyOf1<"abc">)]
+///     name: String,
+///
+///     #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+///     #[nommy(inner_parser = AnyOf1<"0123456789">, seperated_by = Tag<",">)]
+///     args: Vec<usize>,
+/// }
+///
+/// let mut gen = Generator::new(42);
+/// for _ in 0..100 {
+///     let input: String = generate::<Call, _, _>(&mut gen);
+///     parse_terminated::<Call, _>(input.chars()).unwrap();
+/// }
+/// ```
+pub trait Generate<T> {
+    /// Writes a random input accepted by `Self` to the end of output
+    fn generate(gen: &mut Generator, output: &mut impl Extend<T>);
+}
+
+/// `generate` collects the output of [`P::generate`](Generate::generate) into a new collection, usually a
+/// [`String`] or `Vec<u8>`
+///
+/// ```
+/// use nommy::{generate, text::Tag, Generator};
+/// let s: String = generate::<[Tag<"ab">; 2], _, _>(&mut Generator::new(0));
+/// assert_eq!(s, "abab");
+/// ```
+pub fn generate<P: Generate<T>, T, O: Default + Extend<T>>(gen: &mut Generator) -> O {
+    let mut output = O::default();
+    P::generate(gen, &mut output);
+    output
+}
+
+/// The state passed through [`Generate`]. Holds the seeded [`Rng`] and
+/// keeps track of how deeply nested the current value is.
+///
+/// Once [`max_depth`](Generator::max_depth) is reached, repetitions stop (`Vec` and
+/// `Option` generate nothing, `Vec1` generates a single value) and derived enums only
+/// pick variants that don't contain themselves. This is what stops recursive grammars
+/// from growing forever.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    rng: Rng,
+    depth: usize,
+    max_depth: usize,
+    max_repeat: usize,
+}
+
+impl Generator {
+    /// Creates a new Generator from the given seed, with a max depth of 8 and at most 4 repetitions
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            depth: 0,
+            max_depth: 8,
+            max_repeat: 4,
+        }
+    }
+
+    /// Sets how deep values can nest before the generator stops repeating and recursing
+    #[must_use]
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the most extra values a repetition will generate
+    #[must_use]
+    pub const fn max_repeat(mut self, max_repeat: usize) -> Self {
+        self.max_repeat = max_repeat;
+        self
+    }
+
+    /// The random number generator
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    /// How many values deep the generator currently is
+    #[must_use]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Whether the max depth has been reached
+    #[must_use]
+    pub const fn exhausted(&self) -> bool {
+        self.depth >= self.max_depth
+    }
+
+    /// Picks how many times to repeat, between 0 and the max repeat.
+    /// Always 0 once exhausted
+    pub fn repeat(&mut self) -> usize {
+        if self.exhausted() {
+            0
+        } else {
+            self.rng.below(self.max_repeat + 1)
+        }
+    }
+
+    /// Picks how many times to repeat, between `min` and `max`.
+    /// Always `min` once exhausted
+    pub fn repeat_between(&mut self, min: usize, max: usize) -> usize {
+        min.saturating_add(self.repeat()).min(max)
+    }
+
+    /// Runs `f` one level deeper
+    pub fn nested<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.depth += 1;
+        let r = f(self);
+        self.depth -= 1;
+        r
+    }
+
+    /// Picks the index of a variant. `recursive` says which variants contain the enum itself.
+    /// Once exhausted, only the variants that aren't recursive are picked, if there are any
+    pub fn choose_variant(&mut self, recursive: &[bool]) -> usize {
+        let terminal = recursive.iter().filter(|r| !**r).count();
+        if self.exhausted() && terminal > 0 {
+            let n = self.rng.below(terminal);
+            recursive
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| !**r)
+                .nth(n)
+                .map_or(0, |(i, _)| i)
+        } else {
+            self.rng.below(recursive.len())
+        }
+    }
+}
+
+/// Generates `P` half of the time, never once exhausted
+impl<P: Generate<T>, T> Generate<T> for Option<P> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<T>) {
+        if !gen.exhausted() && gen.rng().gen_bool() {
+            P::generate(gen, output);
+        }
+    }
+}
+
+/// Generates `P` up to the max repeat times
+impl<P: Generate<T>, T> Generate<T> for Vec<P> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<T>) {
+        for _ in 0..gen.repeat() {
+            P::generate(gen, output);
+        }
+    }
+}
+
+/// Generates `P` at least once, and up to the max repeat more times
+impl<P: Generate<T>, T> Generate<T> for Vec1<P> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<T>) {
+        for _ in 0..=gen.repeat() {
+            P::generate(gen, output);
+        }
+    }
+}
+
+/// Generates `P` `N` times
+impl<P: Generate<T>, T, const N: usize> Generate<T> for [P; N] {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<T>) {
+        for _ in 0..N {
+            P::generate(gen, output);
+        }
+    }
+}
+
+/// Generates `P`
+impl<P: Generate<T>, T> Generate<T> for Box<P> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<T>) {
+        P::generate(gen, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_terminated, text::OneOf};
+
+    #[test]
+    fn exhausted_stops_repeating() {
+        let mut gen = Generator::new(1).max_depth(0);
+        let s: String = generate::<Vec<OneOf<"ab">>, _, _>(&mut gen);
+        assert_eq!(s, "");
+        let s: String = generate::<Vec1<OneOf<"ab">>, _, _>(&mut gen);
+        assert_eq!(s.len(), 1);
+        let s: String = generate::<Option<OneOf<"ab">>, _, _>(&mut gen);
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn generated_input_parses() {
+        let mut gen = Generator::new(7);
+        for _ in 0..100 {
+            let s: String = generate::<Vec1<Option<OneOf<"xyz">>>, _, _>(&mut gen);
+            parse_terminated::<Vec<OneOf<"xyz">>, _>(s.chars()).unwrap();
+        }
+    }
+
+    #[test]
+    fn choose_variant() {
+        let mut gen = Generator::new(3).max_depth(1);
+        assert!((0..100).all(|_| gen.choose_variant(&[true, false, true]) < 3));
+        gen.nested(|gen| {
+            assert!((0..100).all(|_| gen.choose_variant(&[true, false, true]) == 1));
+            assert!((0..100).all(|_| gen.choose_variant(&[true, true]) < 2));
+        });
+    }
+}
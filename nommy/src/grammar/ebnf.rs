@@ -0,0 +1,252 @@
+use super::{Grammar, Rule};
+
+/// How tightly an expression binds, lowest first.
+/// Expressions are wrapped in parentheses when written somewhere that binds tighter
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+enum Precedence {
+    Choice,
+    Sequence,
+    Postfix,
+}
+
+/// The most copies of a rule that a repetition is written out as.
+/// Longer repetitions are written once, followed by a comment with the count
+const MAX_UNROLLED: usize = 4;
+
+pub fn write(grammar: &Grammar) -> String {
+    let mut output = String::new();
+    for (name, rule) in grammar.rules() {
+        output.push_str(name);
+        output.push_str(" ::= ");
+        output.push_str(&expr(rule, Precedence::Choice));
+        output.push('\n');
+    }
+    output
+}
+
+/// Writes a single rule as an EBNF expression
+pub fn expr_string(rule: &Rule) -> String {
+    expr(rule, Precedence::Choice)
+}
+
+fn expr(rule: &Rule, outer: Precedence) -> String {
+    let (s, inner) = match rule {
+        Rule::Terminal(s) => terminal(s),
+        Rule::CharSet { chars, negated } => (char_set(chars, *negated), Precedence::Postfix),
+        Rule::NonTerminal(name) => (name.clone(), Precedence::Postfix),
+        Rule::Special(s) => (format!("/* {} */", s), Precedence::Postfix),
+        Rule::Sequence(rules) if rules.is_empty() => ("\"\"".to_string(), Precedence::Postfix),
+        Rule::Sequence(rules) => (join(rules, " ", Precedence::Sequence), Precedence::Sequence),
+        Rule::Choice(rules) => (join(rules, " | ", Precedence::Choice), Precedence::Choice),
+        Rule::Optional(rule) => (
+            format!("{}?", expr(rule, Precedence::Postfix)),
+            Precedence::Postfix,
+        ),
+        Rule::Repeat {
+            rule,
+            min,
+            max: None,
+            separator: None,
+        } if *min <= 1 => (postfix(rule, *min), Precedence::Postfix),
+        Rule::Repeat {
+            rule,
+            min,
+            max,
+            separator,
+        } if max.unwrap_or(*min) > MAX_UNROLLED => {
+            let repeat = Rule::Repeat {
+                rule: rule.clone(),
+                min: (*min).min(1),
+                max: None,
+                separator: separator.clone(),
+            };
+            let label = repeat_label(*min, *max).unwrap_or_default();
+            (
+                format!("{} /* {} */", expr(&repeat, Precedence::Sequence), label),
+                Precedence::Sequence,
+            )
+        }
+        Rule::Repeat {
+            rule,
+            min,
+            max,
+            separator,
+        } => {
+            return expr(
+                &expand_repeat(rule, *min, *max, separator.as_deref()),
+                outer,
+            )
+        }
+    };
+    if inner < outer {
+        format!("({})", s)
+    } else {
+        s
+    }
+}
+
+fn join(rules: &[Rule], sep: &str, precedence: Precedence) -> String {
+    // a choice inside a choice still needs parentheses to be readable
+    let precedence = match precedence {
+        Precedence::Choice => Precedence::Sequence,
+        p => p,
+    };
+    rules
+        .iter()
+        .map(|r| expr(r, precedence))
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+/// Rewrites a repetition using only sequences, options, `*` and `+`
+fn expand_repeat(rule: &Rule, min: usize, max: Option<usize>, separator: Option<&Rule>) -> Rule {
+    // the second and later repetitions
+    let next = match separator {
+        Some(sep) => Rule::sequence(vec![sep.clone(), rule.clone()]),
+        None => rule.clone(),
+    };
+
+    if min == 0 {
+        if max == Some(0) {
+            return Rule::empty();
+        }
+        return Rule::optional(expand_repeat(rule, 1, max, separator));
+    }
+
+    let mut rules = vec![rule.clone()];
+    rules.extend(std::iter::repeat(next.clone()).take(min - 1));
+    match max {
+        None => rules.push(Rule::Repeat {
+            rule: Box::new(next),
+            min: 0,
+            max: None,
+            separator: None,
+        }),
+        Some(max) => {
+            rules.extend(std::iter::repeat(Rule::optional(next)).take(max.saturating_sub(min)));
+        }
+    }
+    Rule::sequence(rules)
+}
+
+/// Describes how many times a repetition matches, unless it's just `*` or `+`
+pub fn repeat_label(min: usize, max: Option<usize>) -> Option<String> {
+    match (min, max) {
+        (0..=1, None) => None,
+        (min, None) => Some(format!("at least {}", min)),
+        (min, Some(max)) if min == max => Some(format!("{} times", min)),
+        (min, Some(max)) => Some(format!("{} to {} times", min, max)),
+    }
+}
+
+/// A repetition that can't be expanded any further
+fn postfix(rule: &Rule, min: usize) -> String {
+    let op = if min == 0 { '*' } else { '+' };
+    format!("{}{}", expr(rule, Precedence::Postfix), op)
+}
+
+fn terminal(s: &str) -> (String, Precedence) {
+    if s.is_empty() {
+        return ("\"\"".to_string(), Precedence::Postfix);
+    }
+
+    let mut parts = Vec::new();
+    let mut run = String::new();
+    for c in s.chars() {
+        if c.is_control() || c == '"' {
+            if !run.is_empty() {
+                parts.push(format!("\"{}\"", run));
+                run.clear();
+            }
+            if c == '"' {
+                parts.push("'\"'".to_string());
+            } else {
+                parts.push(hex(c));
+            }
+        } else {
+            run.push(c);
+        }
+    }
+    if !run.is_empty() {
+        parts.push(format!("\"{}\"", run));
+    }
+
+    let precedence = if parts.len() == 1 {
+        Precedence::Postfix
+    } else {
+        Precedence::Sequence
+    };
+    (parts.join(" "), precedence)
+}
+
+fn char_set(chars: &str, negated: bool) -> String {
+    let mut output = String::from(if negated { "[^" } else { "[" });
+    for c in chars.chars() {
+        if c.is_control() || c.is_whitespace() || "[]^-#".contains(c) {
+            output.push_str(&hex(c));
+        } else {
+            output.push(c);
+        }
+    }
+    output.push(']');
+    output
+}
+
+fn hex(c: char) -> String {
+    format!("#x{:X}", c as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(s: &str) -> Rule {
+        Rule::Terminal(s.to_string())
+    }
+
+    #[test]
+    fn terminals() {
+        assert_eq!(terminal("abc").0, "\"abc\"");
+        assert_eq!(terminal("a\"b").0, "\"a\" '\"' \"b\"");
+        assert_eq!(terminal("\r\n").0, "#xD #xA");
+        assert_eq!(char_set("a-z ", true), "[^a#x2Dz#x20]");
+    }
+
+    #[test]
+    fn precedence() {
+        let rule = Rule::sequence(vec![
+            Rule::choice(vec![t("a"), t("b")]),
+            Rule::optional(Rule::sequence(vec![t("c"), t("d")])),
+            Rule::repeat(t("\r\n"), 0),
+        ]);
+        assert_eq!(
+            expr(&rule, Precedence::Choice),
+            "(\"a\" | \"b\") (\"c\" \"d\")? (#xD #xA)*"
+        );
+    }
+
+    #[test]
+    fn repeats() {
+        let repeat = |min, max, sep: Option<Rule>| Rule::Repeat {
+            rule: Box::new(t("a")),
+            min,
+            max,
+            separator: sep.map(Box::new),
+        };
+        let e = |r| expr(&r, Precedence::Choice);
+        assert_eq!(e(repeat(0, None, None)), "\"a\"*");
+        assert_eq!(e(repeat(1, None, None)), "\"a\"+");
+        assert_eq!(e(repeat(2, Some(3), None)), "\"a\" \"a\" \"a\"?");
+        assert_eq!(e(repeat(0, None, Some(t(",")))), "(\"a\" (\",\" \"a\")*)?");
+        assert_eq!(e(repeat(2, Some(2), Some(t(",")))), "\"a\" \",\" \"a\"");
+        assert_eq!(e(repeat(100, Some(100), None)), "\"a\"+ /* 100 times */");
+        assert_eq!(
+            e(repeat(0, Some(10), Some(t(",")))),
+            "(\"a\" (\",\" \"a\")*)? /* 0 to 10 times */"
+        );
+        assert_eq!(
+            e(Rule::optional(repeat(5, None, None))),
+            "(\"a\"+ /* at least 5 */)?"
+        );
+    }
+}
@@ -0,0 +1,371 @@
+//! Describing the grammar that a parser accepts.
+//!
+//! Every type that implements [`Describe`] can be turned into a [`Grammar`],
+//! which can then be written out as [EBNF](Grammar::to_ebnf) or as an
+//! [SVG railroad diagram](Grammar::to_svg).
+//!
+//! ```
+//! use nommy::{grammar::Grammar, text::*, Describe, Parse};
+//!
+//! #[derive(Parse, Describe)]
+//! #[nommy(ignore = WhiteSpace)]
+//! struct List {
+//!     #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+//!     #[nommy(inner_parser = AnyOf1<"0123456789">, seperated_by = Tag<",">)]
+//!     items: Vec<usize>,
+//! }
+//!
+//! let grammar = Grammar::of::<List, char>();
+//! assert_eq!(
+//!     grammar.to_ebnf(),
+//!     r#"List ::= "[" WhiteSpace* ([0123456789]+ (WhiteSpace* "," WhiteSpace* [0123456789]+)*)? "]" WhiteSpace*
+//! WhiteSpace ::= [#x20#x9#xA] | #xD #xA
+//! "#
+//! );
+//! ```
+
+mod ebnf;
+mod svg;
+
+use crate::Vec1;
+
+/// Describes the grammar of a parser, so it can be documented.
+/// Types that parse a named part of the grammar register it as a rule with [`Grammar::rule`]
+///
+/// ```
+/// use nommy::{grammar::{Grammar, Rule}, text::Tag, Describe};
+/// let rule = <Option<Tag<"foo">> as Describe<char>>::describe(&mut Grammar::new());
+/// assert_eq!(rule, Rule::optional(Rule::Terminal("foo".to_string())));
+/// ```
+pub trait Describe<T> {
+    /// Describes the input accepted by `Self`
+    fn describe(grammar: &mut Grammar) -> Rule;
+}
+
+/// A single piece of a grammar
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    /// An exact string
+    Terminal(String),
+    /// Any one character in (or not in, if negated) the set
+    CharSet {
+        /// The characters in the set
+        chars: String,
+        /// Whether the set matches the characters that aren't in `chars`
+        negated: bool,
+    },
+    /// A reference to another named rule in the [`Grammar`]
+    NonTerminal(String),
+    /// Input that can't be described with the other rules, such as binary numbers
+    Special(String),
+    /// Each rule one after another
+    Sequence(Vec<Rule>),
+    /// Any one of the rules
+    Choice(Vec<Rule>),
+    /// The rule, or nothing
+    Optional(Box<Rule>),
+    /// The rule, repeated at least `min` times and at most `max` times.
+    /// Each repetition is separated by the `separator` rule
+    Repeat {
+        /// The rule that is repeated
+        rule: Box<Rule>,
+        /// The fewest repetitions allowed
+        min: usize,
+        /// The most repetitions allowed, if limited
+        max: Option<usize>,
+        /// What comes in between each repetition
+        separator: Option<Box<Rule>>,
+    },
+}
+
+impl Rule {
+    /// An empty rule, which matches nothing
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self::Sequence(Vec::new())
+    }
+
+    /// Whether the rule matches nothing
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Sequence(rules) if rules.is_empty())
+    }
+
+    /// Each rule one after another. Nested sequences and empty rules are flattened
+    #[must_use]
+    pub fn sequence(rules: Vec<Self>) -> Self {
+        let mut output = Vec::new();
+        for rule in rules {
+            match rule {
+                Self::Sequence(rules) => output.extend(rules),
+                rule => output.push(rule),
+            }
+        }
+        if output.len() == 1 {
+            output.remove(0)
+        } else {
+            Self::Sequence(output)
+        }
+    }
+
+    /// Any one of the rules. Nested choices are flattened
+    #[must_use]
+    pub fn choice(rules: Vec<Self>) -> Self {
+        let mut output = Vec::new();
+        for rule in rules {
+            match rule {
+                Self::Choice(rules) => output.extend(rules),
+                rule => output.push(rule),
+            }
+        }
+        if output.len() == 1 {
+            output.remove(0)
+        } else {
+            Self::Choice(output)
+        }
+    }
+
+    /// The rule, or nothing
+    #[must_use]
+    pub fn optional(rule: Self) -> Self {
+        if rule.is_empty() {
+            rule
+        } else {
+            Self::Optional(Box::new(rule))
+        }
+    }
+
+    /// The rule, repeated at least `min` times
+    #[must_use]
+    pub fn repeat(rule: Self, min: usize) -> Self {
+        Self::repeat_separated(rule, min, Self::empty())
+    }
+
+    /// The rule, repeated at least `min` times, with `separator` in between each repetition
+    #[must_use]
+    pub fn repeat_separated(rule: Self, min: usize, separator: Self) -> Self {
+        if rule.is_empty() {
+            return rule;
+        }
+        Self::Repeat {
+            rule: Box::new(rule),
+            min,
+            max: None,
+            separator: if separator.is_empty() {
+                None
+            } else {
+                Some(Box::new(separator))
+            },
+        }
+    }
+}
+
+/// A set of named rules, starting with the root
+#[derive(Debug, Clone, Default)]
+pub struct Grammar {
+    rules: Vec<(String, Rule)>,
+    /// The name given to each type described with [`Grammar::rule_of`], by its [`type_name`](std::any::type_name)
+    types: Vec<(&'static str, String)>,
+}
+
+impl Grammar {
+    /// Creates a new, empty grammar
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Describes the grammar of `P`. If `P` doesn't register a named rule,
+    /// its description is added as a rule called `Root`
+    #[must_use]
+    pub fn of<P: Describe<T>, T>() -> Self {
+        let mut grammar = Self::new();
+        let rule = P::describe(&mut grammar);
+        if !matches!(rule, Rule::NonTerminal(_)) {
+            grammar.rules.insert(0, ("Root".to_string(), rule));
+        }
+        grammar
+    }
+
+    /// Adds a named rule to the grammar, described by `f`, returning a [`Rule::NonTerminal`] that refers to it.
+    /// `f` is only called the first time a name is seen, so recursive rules are only described once
+    pub fn rule(&mut self, name: &str, f: impl FnOnce(&mut Self) -> Rule) -> Rule {
+        if self.get(name).is_none() {
+            let i = self.rules.len();
+            self.rules.push((name.to_string(), Rule::empty()));
+            let rule = f(self);
+            self.rules[i].1 = rule;
+        }
+        Rule::NonTerminal(name.to_string())
+    }
+
+    /// Adds the rule for the type `P` to the grammar, described by `f`, returning a [`Rule::NonTerminal`] that refers to it.
+    /// The rule is named after `P` and its generic arguments, so `Pair<A>` becomes `Pair_A`.
+    /// Different types that would get the same name are numbered, starting from `_2`.
+    /// This is what `#[derive(Describe)]` uses
+    pub fn rule_of<P: ?Sized>(&mut self, f: impl FnOnce(&mut Self) -> Rule) -> Rule {
+        let type_name = std::any::type_name::<P>();
+        if let Some((_, name)) = self.types.iter().find(|(t, _)| *t == type_name) {
+            return Rule::NonTerminal(name.clone());
+        }
+
+        let base = short_name(type_name);
+        let mut name = base.clone();
+        let mut n = 1;
+        while self.get(&name).is_some() {
+            n += 1;
+            name = format!("{}_{}", base, n);
+        }
+        self.types.push((type_name, name.clone()));
+        self.rule(&name, f)
+    }
+
+    /// Gets the rule with the given name
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Rule> {
+        self.rules.iter().find(|(n, _)| n == name).map(|(_, r)| r)
+    }
+
+    /// All of the named rules, in the order they were first seen
+    #[must_use]
+    pub fn rules(&self) -> impl Iterator<Item = (&str, &Rule)> {
+        self.rules.iter().map(|(n, r)| (n.as_str(), r))
+    }
+
+    /// Writes the grammar out in the [W3C flavour of EBNF](https://www.w3.org/TR/xml/#sec-notation).
+    /// [`Rule::Special`] rules are written as comments
+    #[must_use]
+    pub fn to_ebnf(&self) -> String {
+        ebnf::write(self)
+    }
+
+    /// Draws the grammar as an SVG railroad diagram, one diagram per rule
+    #[must_use]
+    pub fn to_svg(&self) -> String {
+        svg::write(self)
+    }
+}
+
+/// Turns a [`type_name`](std::any::type_name) into a rule name, dropping the module paths
+/// and joining the generic arguments with `_`
+fn short_name(type_name: &str) -> String {
+    let mut name = String::new();
+    for part in type_name.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')) {
+        let part = part.rsplit("::").next().unwrap_or(part);
+        if !part.is_empty() {
+            if !name.is_empty() {
+                name.push('_');
+            }
+            name.push_str(part);
+        }
+    }
+    name
+}
+
+/// `P`, or nothing
+impl<P: Describe<T>, T> Describe<T> for Option<P> {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        Rule::optional(P::describe(grammar))
+    }
+}
+
+/// `P` repeated any number of times
+impl<P: Describe<T>, T> Describe<T> for Vec<P> {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        Rule::repeat(P::describe(grammar), 0)
+    }
+}
+
+/// `P` repeated at least once
+impl<P: Describe<T>, T> Describe<T> for Vec1<P> {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        Rule::repeat(P::describe(grammar), 1)
+    }
+}
+
+/// `P` repeated exactly `N` times
+impl<P: Describe<T>, T, const N: usize> Describe<T> for [P; N] {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        Rule::Repeat {
+            rule: Box::new(P::describe(grammar)),
+            min: N,
+            max: Some(N),
+            separator: None,
+        }
+    }
+}
+
+/// The same as `P`
+impl<P: Describe<T>, T> Describe<T> for Box<P> {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        P::describe(grammar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::{AnyOf1, Tag};
+
+    #[test]
+    fn sequence_flattens() {
+        let a = || Rule::Terminal("a".to_string());
+        let rule = Rule::sequence(vec![a(), Rule::sequence(vec![a(), a()]), Rule::empty()]);
+        assert_eq!(rule, Rule::Sequence(vec![a(), a(), a()]));
+        assert_eq!(Rule::sequence(vec![Rule::empty(), a()]), a());
+        assert_eq!(Rule::choice(vec![a()]), a());
+    }
+
+    #[test]
+    fn recursive_rules_are_described_once() {
+        struct List;
+        impl Describe<char> for List {
+            fn describe(grammar: &mut Grammar) -> Rule {
+                grammar.rule("List", |grammar| {
+                    Rule::sequence(vec![
+                        Tag::<"(">::describe(grammar),
+                        Rule::repeat(
+                            Rule::choice(vec![
+                                Self::describe(grammar),
+                                AnyOf1::<"ab">::describe(grammar),
+                            ]),
+                            0,
+                        ),
+                        Tag::<")">::describe(grammar),
+                    ])
+                })
+            }
+        }
+
+        let grammar = Grammar::of::<List, char>();
+        assert_eq!(grammar.rules().count(), 1);
+        assert_eq!(grammar.to_ebnf(), "List ::= \"(\" (List | [ab]+)* \")\"\n");
+    }
+
+    #[test]
+    fn rules_of_types_are_named_by_their_arguments() {
+        mod a {
+            pub struct Pair<P>(pub P);
+        }
+        mod b {
+            pub struct Pair<P>(pub P);
+        }
+
+        let mut grammar = Grammar::new();
+        let rule = grammar.rule_of::<a::Pair<Tag<"x">>>(|_| Rule::Terminal("x".to_string()));
+        assert_eq!(rule, Rule::NonTerminal("Pair_Tag_x".to_string()));
+        let rule = grammar.rule_of::<a::Pair<AnyOf1<"ab">>>(|_| Rule::Terminal("y".to_string()));
+        assert_eq!(rule, Rule::NonTerminal("Pair_AnyOf1_ab".to_string()));
+        let rule = grammar.rule_of::<b::Pair<Tag<"x">>>(|_| Rule::Terminal("z".to_string()));
+        assert_eq!(rule, Rule::NonTerminal("Pair_Tag_x_2".to_string()));
+        let rule = grammar.rule_of::<a::Pair<Tag<"x">>>(|_| unreachable!());
+        assert_eq!(rule, Rule::NonTerminal("Pair_Tag_x".to_string()));
+        assert_eq!(grammar.rules().count(), 3);
+    }
+
+    #[test]
+    fn root_rule() {
+        let grammar = Grammar::of::<[Tag<"ab">; 2], char>();
+        assert_eq!(grammar.to_ebnf(), "Root ::= \"ab\" \"ab\"\n");
+    }
+}
@@ -0,0 +1,336 @@
+use std::fmt::Write;
+
+use super::{ebnf, Grammar, Rule};
+
+/// Width of a character of box text
+const CHAR_WIDTH: usize = 8;
+/// Height of a box
+const BOX_HEIGHT: usize = 24;
+/// Space between the text and the sides of a box
+const PADDING: usize = 8;
+/// Length of the line between items in a sequence
+const GAP: usize = 10;
+/// Width of the rails on either side of a choice or loop
+const RAIL: usize = 10;
+/// Vertical space between branches
+const V_GAP: usize = 10;
+/// Height of a rule's name above its diagram
+const TITLE: usize = 24;
+/// Space around each diagram
+const MARGIN: usize = 16;
+
+const STYLE: &str = "path { stroke: #333; stroke-width: 1.5; fill: none; } \
+rect { stroke: #333; stroke-width: 1.5; fill: #ffc; } \
+rect.terminal { fill: #dfd; } \
+rect.special { fill: #eee; stroke-dasharray: 4 2; } \
+text { font: 13px monospace; text-anchor: middle; } \
+text.name { font-weight: bold; text-anchor: start; } \
+text.label { font-size: 11px; }";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Kind {
+    Terminal,
+    NonTerminal,
+    Special,
+}
+
+/// A railroad diagram, laid out left to right along a baseline
+#[derive(Debug)]
+enum Node {
+    Box(Kind, String),
+    Skip,
+    Sequence(Vec<Node>),
+    Choice(Vec<Node>),
+    Loop {
+        item: Box<Node>,
+        back: Box<Node>,
+        label: Option<String>,
+    },
+}
+
+/// The space taken up by a node, relative to where its baseline enters on the left
+#[derive(Debug, Copy, Clone)]
+struct Size {
+    width: usize,
+    up: usize,
+    down: usize,
+}
+
+impl Node {
+    fn new(rule: &Rule) -> Self {
+        match rule {
+            Rule::Terminal(_) | Rule::CharSet { .. } => {
+                Self::Box(Kind::Terminal, ebnf::expr_string(rule))
+            }
+            Rule::NonTerminal(name) => Self::Box(Kind::NonTerminal, name.clone()),
+            Rule::Special(s) => Self::Box(Kind::Special, s.clone()),
+            Rule::Sequence(rules) if rules.is_empty() => Self::Skip,
+            Rule::Sequence(rules) => Self::Sequence(rules.iter().map(Self::new).collect()),
+            Rule::Choice(rules) => Self::Choice(rules.iter().map(Self::new).collect()),
+            Rule::Optional(rule) => Self::Choice(vec![Self::Skip, Self::new(rule)]),
+            Rule::Repeat {
+                rule,
+                min,
+                max,
+                separator,
+            } => {
+                let node = Self::Loop {
+                    item: Box::new(Self::new(rule)),
+                    back: Box::new(separator.as_deref().map_or(Self::Skip, Self::new)),
+                    label: ebnf::repeat_label(*min, *max),
+                };
+                if *min == 0 {
+                    Self::Choice(vec![Self::Skip, node])
+                } else {
+                    node
+                }
+            }
+        }
+    }
+
+    fn size(&self) -> Size {
+        match self {
+            Self::Box(_, text) => Size {
+                width: text.chars().count() * CHAR_WIDTH + 2 * PADDING,
+                up: BOX_HEIGHT / 2,
+                down: BOX_HEIGHT / 2,
+            },
+            Self::Skip => Size {
+                width: 0,
+                up: 0,
+                down: 0,
+            },
+            Self::Sequence(nodes) => {
+                let sizes: Vec<Size> = nodes.iter().map(Self::size).collect();
+                Size {
+                    width: sizes.iter().map(|s| s.width).sum::<usize>()
+                        + GAP * sizes.len().saturating_sub(1),
+                    up: sizes.iter().map(|s| s.up).max().unwrap_or(0),
+                    down: sizes.iter().map(|s| s.down).max().unwrap_or(0),
+                }
+            }
+            Self::Choice(nodes) => {
+                let sizes: Vec<Size> = nodes.iter().map(Self::size).collect();
+                let first = sizes.first().copied().unwrap_or(Size {
+                    width: 0,
+                    up: 0,
+                    down: 0,
+                });
+                Size {
+                    width: sizes.iter().map(|s| s.width).max().unwrap_or(0) + 4 * RAIL,
+                    up: first.up,
+                    down: first.down
+                        + sizes[1..]
+                            .iter()
+                            .map(|s| V_GAP + s.up + s.down)
+                            .sum::<usize>(),
+                }
+            }
+            Self::Loop { item, back, label } => {
+                let item = item.size();
+                let back = back.size();
+                Size {
+                    width: item.width.max(back.width) + 4 * RAIL,
+                    up: item.up,
+                    down: item.down
+                        + V_GAP
+                        + back.up
+                        + back.down
+                        + if label.is_some() { V_GAP + 4 } else { 0 },
+                }
+            }
+        }
+    }
+
+    /// Draws the node with its baseline entering at (x, y)
+    fn draw(&self, x: usize, y: usize, out: &mut String) {
+        let size = self.size();
+        match self {
+            Self::Box(kind, text) => {
+                let class = match kind {
+                    Kind::Terminal => " class=\"terminal\" rx=\"10\"",
+                    Kind::NonTerminal => "",
+                    Kind::Special => " class=\"special\"",
+                };
+                let _ = write!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/><text x=\"{}\" y=\"{}\">{}</text>",
+                    x,
+                    y - size.up,
+                    size.width,
+                    BOX_HEIGHT,
+                    class,
+                    x + size.width / 2,
+                    y + 4,
+                    escape(text)
+                );
+            }
+            Self::Skip => {}
+            Self::Sequence(nodes) => {
+                let mut x = x;
+                for (i, node) in nodes.iter().enumerate() {
+                    if i > 0 {
+                        line(out, x, y, x + GAP);
+                        x += GAP;
+                    }
+                    node.draw(x, y, out);
+                    x += node.size().width;
+                }
+            }
+            Self::Choice(nodes) => {
+                let mut branch_y = y;
+                for (i, node) in nodes.iter().enumerate() {
+                    let node_size = node.size();
+                    if i == 0 {
+                        line(out, x, y, x + 2 * RAIL);
+                        line(out, x + 2 * RAIL + node_size.width, y, x + size.width);
+                    } else {
+                        branch_y += V_GAP + node_size.up;
+                        let _ = write!(
+                            out,
+                            "<path d=\"M{} {} V{} H{} M{} {} H{} V{}\"/>",
+                            x + RAIL,
+                            y,
+                            branch_y,
+                            x + 2 * RAIL,
+                            x + 2 * RAIL + node_size.width,
+                            branch_y,
+                            x + size.width - RAIL,
+                            y
+                        );
+                    }
+                    node.draw(x + 2 * RAIL, branch_y, out);
+                    branch_y += node_size.down;
+                }
+            }
+            Self::Loop { item, back, label } => {
+                let item_size = item.size();
+                let back_size = back.size();
+                line(out, x, y, x + 2 * RAIL);
+                item.draw(x + 2 * RAIL, y, out);
+                line(out, x + 2 * RAIL + item_size.width, y, x + size.width);
+
+                let back_y = y + item_size.down + V_GAP + back_size.up;
+                let back_x = x + 2 * RAIL + (size.width - 4 * RAIL - back_size.width) / 2;
+                let _ = write!(
+                    out,
+                    "<path d=\"M{} {} V{} H{} M{} {} H{} V{}\"/>",
+                    x + size.width - RAIL,
+                    y,
+                    back_y,
+                    back_x + back_size.width,
+                    back_x,
+                    back_y,
+                    x + RAIL,
+                    y
+                );
+                back.draw(back_x, back_y, out);
+
+                if let Some(label) = label {
+                    let _ = write!(
+                        out,
+                        "<text class=\"label\" x=\"{}\" y=\"{}\">{}</text>",
+                        x + size.width / 2,
+                        y + size.down,
+                        escape(label)
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn line(out: &mut String, x1: usize, y: usize, x2: usize) {
+    let _ = write!(out, "<path d=\"M{} {} H{}\"/>", x1, y, x2);
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn write(grammar: &Grammar) -> String {
+    let mut body = String::new();
+    let mut width = 0;
+    let mut y = 0;
+
+    for (name, rule) in grammar.rules() {
+        let node = Node::new(rule);
+        let size = node.size();
+
+        let _ = write!(
+            body,
+            "<text class=\"name\" x=\"{}\" y=\"{}\">{}</text>",
+            MARGIN,
+            y + MARGIN + 12,
+            escape(name)
+        );
+
+        // the diagram starts and ends with a short line and a stop
+        let baseline = y + MARGIN + TITLE + size.up;
+        let start = MARGIN;
+        let end = start + GAP + size.width + GAP;
+        let _ = write!(
+            body,
+            "<path d=\"M{} {} V{} M{} {} H{}\"/>",
+            start,
+            baseline - 8,
+            baseline + 8,
+            start,
+            baseline,
+            start + GAP
+        );
+        node.draw(start + GAP, baseline, &mut body);
+        let _ = write!(
+            body,
+            "<path d=\"M{} {} H{} M{} {} V{}\"/>",
+            end - GAP,
+            baseline,
+            end,
+            end,
+            baseline - 8,
+            baseline + 8
+        );
+
+        width = width.max(end + MARGIN);
+        y = baseline + size.down + MARGIN;
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\"><style>{}</style>{}</svg>\n",
+        STYLE,
+        body,
+        w = width,
+        h = y,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes() {
+        let terminal = Node::Box(Kind::Terminal, "\"ab\"".to_string());
+        assert_eq!(terminal.size().width, 4 * CHAR_WIDTH + 2 * PADDING);
+
+        let rule = Rule::optional(Rule::Terminal("ab".to_string()));
+        let size = Node::new(&rule).size();
+        assert_eq!(size.width, terminal.size().width + 4 * RAIL);
+        assert_eq!(size.up, 0);
+        assert_eq!(size.down, V_GAP + BOX_HEIGHT);
+    }
+
+    #[test]
+    fn svg() {
+        let mut grammar = Grammar::new();
+        grammar.rule("Tag", |_| Rule::Terminal("<a&b>".to_string()));
+        let svg = grammar.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains(">Tag</text>"));
+        assert!(svg.contains(">&quot;&lt;a&amp;b&gt;&quot;</text>"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
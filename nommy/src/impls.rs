@@ -6,33 +6,37 @@ use std::mem::MaybeUninit;
 /// Result is `None` if parsing `P` fails, otherwise, result is `Some(p)`
 impl<P: Parse<T>, T> Parse<T> for Option<P> {
     fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
-        let mut cursor = input.cursor();
-        match P::parse(&mut cursor) {
-            Ok(p) => {
-                if cfg!(debug_assertions) && cursor.position() == 0 {
-                    panic!("parsing succeeded with 0 elements read - fix: remove `Option<_>`");
+        crate::context::check(input, |input| {
+            let mut cursor = input.cursor();
+            match P::parse(&mut cursor) {
+                Ok(p) => {
+                    if cfg!(debug_assertions) && cursor.position() == 0 {
+                        panic!("parsing succeeded with 0 elements read - fix: remove `Option<_>`");
+                    }
+                    let pos = cursor.position();
+                    input.fast_forward(pos);
+                    Ok(Some(p))
                 }
-                let pos = cursor.position();
-                input.fast_forward(pos);
-                Ok(Some(p))
+                Err(_) => Ok(None),
             }
-            Err(_) => Ok(None),
-        }
+        })
     }
 
     fn peek(input: &mut impl Buffer<T>) -> bool {
-        let mut cursor = input.cursor();
+        crate::context::check_peek(input, |input| {
+            let mut cursor = input.cursor();
 
-        if P::peek(&mut cursor) {
-            if cfg!(debug_assertions) && cursor.position() == 0 {
-                panic!("parsing succeeded with 0 elements read - fix: remove `Option<_>`");
+            if P::peek(&mut cursor) {
+                if cfg!(debug_assertions) && cursor.position() == 0 {
+                    panic!("parsing succeeded with 0 elements read - fix: remove `Option<_>`");
+                }
+                let pos = cursor.position();
+                input.fast_forward(pos);
             }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
 
-        // Option should always return true for peek
-        true
+            // Option should always return true for peek
+            true
+        })
     }
 }
 
@@ -49,37 +53,41 @@ impl<P: Parse<T>, T> Parse<T> for Vec<P> {
     /// ```
     #[track_caller]
     fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
-        let mut output = Self::new();
-        loop {
-            let mut cursor = input.cursor();
-            match P::parse(&mut cursor) {
-                Ok(p) => output.push(p),
-                Err(_) => break,
-            }
-            let pos = cursor.position();
-            if cfg!(debug_assertions) && pos == 0 {
-                panic!("parsing succeeded with 0 elements read. infinite loop detected");
+        crate::context::check(input, |input| {
+            let mut output = Self::new();
+            loop {
+                let mut cursor = input.cursor();
+                match P::parse(&mut cursor) {
+                    Ok(p) => output.push(p),
+                    Err(_) => break,
+                }
+                let pos = cursor.position();
+                if cfg!(debug_assertions) && pos == 0 {
+                    panic!("parsing succeeded with 0 elements read. infinite loop detected");
+                }
+                input.fast_forward(pos);
             }
-            input.fast_forward(pos);
-        }
 
-        Ok(output)
+            Ok(output)
+        })
     }
 
     #[track_caller]
     fn peek(input: &mut impl Buffer<T>) -> bool {
-        loop {
-            let mut cursor = input.cursor();
-            if !P::peek(&mut cursor) {
-                break;
-            }
-            let pos = cursor.position();
-            if cfg!(debug_assertions) && pos == 0 {
-                panic!("parsing succeeded with 0 elements read. infinite loop detected");
+        crate::context::check_peek(input, |input| {
+            loop {
+                let mut cursor = input.cursor();
+                if !P::peek(&mut cursor) {
+                    break;
+                }
+                let pos = cursor.position();
+                if cfg!(debug_assertions) && pos == 0 {
+                    panic!("parsing succeeded with 0 elements read. infinite loop detected");
+                }
+                input.fast_forward(pos);
             }
-            input.fast_forward(pos);
-        }
-        true
+            true
+        })
     }
 }
 
@@ -109,41 +117,45 @@ impl<P> From<Vec1<P>> for Vec<P> {
 /// Must parse `P` at least once
 impl<P: Parse<T>, T> Parse<T> for Vec1<P> {
     fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
-        let mut output = vec![P::parse(input)?];
-        loop {
-            let mut cursor = input.cursor();
-            match P::parse(&mut cursor) {
-                Ok(p) => output.push(p),
-                Err(_) => break,
-            }
-            if cfg!(debug_assertions) && cursor.position() == 0 {
-                panic!("parsing succeeded with 0 elements read. infinite loop detected");
+        crate::context::check(input, |input| {
+            let mut output = vec![P::parse(input)?];
+            loop {
+                let mut cursor = input.cursor();
+                match P::parse(&mut cursor) {
+                    Ok(p) => output.push(p),
+                    Err(_) => break,
+                }
+                if cfg!(debug_assertions) && cursor.position() == 0 {
+                    panic!("parsing succeeded with 0 elements read. infinite loop detected");
+                }
+                let pos = cursor.position();
+                input.fast_forward(pos);
             }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
 
-        Ok(Self(output))
+            Ok(Self(output))
+        })
     }
 
     fn peek(input: &mut impl Buffer<T>) -> bool {
-        if !P::peek(input) {
-            return false;
-        }
-
-        loop {
-            let mut cursor = input.cursor();
-            if !P::peek(&mut cursor) {
-                break;
+        crate::context::check_peek(input, |input| {
+            if !P::peek(input) {
+                return false;
             }
-            if cfg!(debug_assertions) && cursor.position() == 0 {
-                panic!("parsing succeeded with 0 elements read. infinite loop detected");
+
+            loop {
+                let mut cursor = input.cursor();
+                if !P::peek(&mut cursor) {
+                    break;
+                }
+                if cfg!(debug_assertions) && cursor.position() == 0 {
+                    panic!("parsing succeeded with 0 elements read. infinite loop detected");
+                }
+                let pos = cursor.position();
+                input.fast_forward(pos);
             }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
 
-        true
+            true
+        })
     }
 }
 
@@ -183,11 +195,11 @@ impl<P: Parse<T>, T, const N: usize> Parse<T> for [P; N] {
 /// Parse
 impl<P: Parse<T>, T> Parse<T> for Box<P> {
     fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
-        Ok(Box::new(P::parse(input)?))
+        crate::context::parse(input, |input| Ok(Self::new(P::parse(input)?)))
     }
 
     fn peek(input: &mut impl Buffer<T>) -> bool {
-        P::peek(input)
+        crate::context::peek(input, P::peek)
     }
 }
 
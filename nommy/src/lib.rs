@@ -62,8 +62,20 @@
 mod buffer;
 pub use buffer::*;
 pub mod bytes;
+pub mod context;
+mod generate;
+pub use generate::*;
+pub mod grammar;
+pub use grammar::Describe;
 mod impls;
+pub mod memo;
+pub mod pratt;
+mod print;
+pub use print::*;
+pub mod testing;
 pub mod text;
+mod utf8;
+pub use utf8::*;
 pub mod vec;
 
 use eyre::Context;
@@ -122,6 +134,71 @@ pub use impls::Vec1;
 /// ```
 pub use nommy_derive::Parse;
 
+/// Derive Print for structs or enums, using the same `#[nommy]` attributes as [`Parse`](derive@Parse)
+///
+/// ```
+/// use nommy::{parse, print, text::*, Parse, Print};
+///
+/// type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+///
+/// #[derive(Debug, Parse, Print, PartialEq)]
+/// #[nommy(ignore = WhiteSpace)]
+/// struct List {
+///     #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+///     #[nommy(inner_parser = Letters, seperated_by = Tag<",">)]
+///     items: Vec<String>,
+/// }
+///
+/// let list: List = parse("[ foo,bar]".chars()).unwrap();
+/// let output: String = print(&list);
+/// assert_eq!(output, "[ foo , bar] ");
+/// assert_eq!(parse::<List, _>(output.chars()).unwrap(), list);
+/// ```
+pub use nommy_derive::Print;
+
+/// Derive Generate for structs or enums, using the same `#[nommy]` attributes as [`Parse`](derive@Parse)
+///
+/// Enum variants that contain the enum itself are not picked once the [`Generator`] reaches its max depth
+///
+/// ```
+/// use nommy::{generate, parse_terminated, text::*, Generate, Generator, Parse};
+///
+/// #[derive(Debug, Parse, Generate)]
+/// #[nommy(ignore = WhiteSpace)]
+/// #[nommy(parse_type = char)]
+/// enum Exp {
+///     #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+///     List(Vec<Exp>),
+///     Number(#[nommy(parser = AnyOf1<"0123456789">)] usize),
+/// }
+///
+/// let mut gen = Generator::new(0).max_depth(4);
+/// for _ in 0..100 {
+///     let input: String = generate::<Exp, _, _>(&mut gen);
+///     parse_terminated::<Exp, _>(input.chars()).unwrap();
+/// }
+/// ```
+pub use nommy_derive::Generate;
+
+/// Derive Describe for structs or enums, using the same `#[nommy]` attributes as [`Parse`](derive@Parse).
+/// Each derived type becomes a named rule in the [`Grammar`](grammar::Grammar)
+///
+/// ```
+/// use nommy::{grammar::Grammar, text::*, Describe, Parse};
+///
+/// #[derive(Parse, Describe)]
+/// #[nommy(parse_type = char)]
+/// enum Exp {
+///     #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+///     List(Vec<Exp>),
+///     Number(#[nommy(parser = AnyOf1<"0123456789">)] usize),
+/// }
+///
+/// let grammar = Grammar::of::<Exp, char>();
+/// assert_eq!(grammar.to_ebnf(), "Exp ::= \"(\" Exp* \")\" | [0123456789]+\n");
+/// ```
+pub use nommy_derive::Describe;
+
 pub use eyre;
 
 /// `parse` takes the given iterator, putting it through [`P::parse`](Parse::parse)
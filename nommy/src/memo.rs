@@ -0,0 +1,642 @@
+//! Packrat memoization, so that a type is parsed at most once at each position of the input.
+//!
+//! Types with `#[nommy(memoize)]` save the result of parsing them into the [`Memo`] of the buffer
+//! being read, keyed by the type and the offset it was parsed at. When the same type is parsed
+//! at the same offset again, such as when several variants of an enum start with it, the saved
+//! result is used instead. Memoized types must be `Clone + Send + Sync + 'static`.
+//!
+//! Memoization also lets a type be directly left recursive, see [`parse`]. Indirect left recursion,
+//! where a type reaches itself through another type without reading any input, fails instead.
+//!
+//! [`Buf`](crate::Buf), [`SliceBuf`](crate::SliceBuf) and [`Utf8Buf`](crate::Utf8Buf) keep a memo,
+//! other buffers parse as normal
+//!
+//! ```
+//! use nommy::{parse, text::*, Parse};
+//!
+//! #[derive(Debug, Clone, PartialEq, Parse)]
+//! #[nommy(memoize)]
+//! struct Word(#[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)] String);
+//!
+//! #[derive(Debug, PartialEq, Parse)]
+//! enum Expr {
+//!     // `Word` is parsed once, even though `Bare` is tried after `Call` fails
+//!     Call(Word, #[nommy(prefix = Tag<"(">, suffix = Tag<")">)] Word),
+//!     Bare(Word),
+//! }
+//!
+//! let expr: Expr = parse("foo".chars()).unwrap();
+//! assert_eq!(expr, Expr::Bare(Word("foo".to_string())));
+//! ```
+
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use crate::{
+    context::{DepthExceeded, Interrupted},
+    eyre, Buffer, Cursor,
+};
+
+/// A saved result
+enum Entry {
+    /// Parsed successfully, reading `len` values
+    Parsed(Box<dyn Any + Send + Sync>, usize),
+    /// Failed to parse, with the error message
+    Failed(String),
+    /// Only peeked, reading `len` values if it matched
+    Peeked(Option<usize>),
+    /// Being parsed or peeked further up the stack. Set to true if it's reached again at the same offset,
+    /// which means it's left recursive
+    InProgress(bool),
+    /// The result of a left recursive parse that's being grown, reading `len` values. It's lent to the first
+    /// attempt that reaches it rather than copied, and `missed` is set if another attempt reached it after that
+    Seed {
+        seed: Option<Box<dyn Any + Send + Sync>>,
+        len: usize,
+        missed: bool,
+    },
+}
+
+/// The saved results of memoized parsers, keyed by the offset they were parsed at and the parser's type.
+/// Buffers that support memoization keep one of these, see [`Buffer::memo`]
+#[derive(Default)]
+pub struct Memo {
+    entries: BTreeMap<usize, HashMap<TypeId, Entry>>,
+}
+
+impl fmt::Debug for Memo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Memo").field("len", &self.len()).finish()
+    }
+}
+
+impl Memo {
+    /// Creates a new, empty memo
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many results are saved
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.values().map(HashMap::len).sum()
+    }
+
+    /// Whether no results are saved
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Forgets the results saved before `offset`.
+    /// Buffers call this as they're read, since nothing can be parsed from before them again
+    pub fn forget_before(&mut self, offset: usize) {
+        while let Some(&first) = self.entries.keys().next() {
+            if first >= offset {
+                break;
+            }
+            self.entries.remove(&first);
+        }
+    }
+
+    fn insert<P: 'static>(&mut self, offset: usize, entry: Entry) {
+        self.entries
+            .entry(offset)
+            .or_default()
+            .insert(TypeId::of::<P>(), entry);
+    }
+
+    fn remove<P: 'static>(&mut self, offset: usize) {
+        if let Some(entries) = self.entries.get_mut(&offset) {
+            entries.remove(&TypeId::of::<P>());
+            if entries.is_empty() {
+                self.entries.remove(&offset);
+            }
+        }
+    }
+
+    /// The saved result of parsing `P`, along with how many values it read
+    fn parsed<P: Clone + 'static>(&mut self, offset: usize) -> Option<Result<(P, usize), String>> {
+        let entry = self.entries.get_mut(&offset)?.get_mut(&TypeId::of::<P>())?;
+        match entry {
+            Entry::Parsed(p, len) => {
+                let p = p
+                    .downcast_ref::<P>()
+                    .expect("memo entries are keyed by type");
+                Some(Ok((p.clone(), *len)))
+            }
+            Entry::Failed(err) => Some(Err(err.clone())),
+            Entry::Peeked(_) => None,
+            Entry::InProgress(recursed) => {
+                *recursed = true;
+                Some(Err(left_recursion::<P>(offset)))
+            }
+            Entry::Seed { seed, len, missed } => {
+                if let Some(seed) = seed.take() {
+                    let seed = seed
+                        .downcast::<P>()
+                        .expect("memo entries are keyed by type");
+                    Some(Ok((*seed, *len)))
+                } else {
+                    *missed = true;
+                    Some(Err(left_recursion::<P>(offset)))
+                }
+            }
+        }
+    }
+
+    /// Takes back the seed lent to an attempt at growing `P`, if the attempt didn't use it,
+    /// along with whether another attempt missed out on it
+    fn take_seed<P: 'static>(&mut self, offset: usize) -> (Option<P>, bool) {
+        let entry = self
+            .entries
+            .get_mut(&offset)
+            .and_then(|entries| entries.remove(&TypeId::of::<P>()));
+        match entry {
+            Some(Entry::Seed { seed, missed, .. }) => {
+                let seed = seed.map(|seed| {
+                    *seed
+                        .downcast::<P>()
+                        .expect("memo entries are keyed by type")
+                });
+                (seed, missed)
+            }
+            _ => (None, true),
+        }
+    }
+
+    /// Whether `P` matched, and how many values it read
+    fn peeked<P: 'static>(&mut self, offset: usize) -> Option<(bool, usize)> {
+        let entry = self.entries.get_mut(&offset)?.get_mut(&TypeId::of::<P>())?;
+        match entry {
+            Entry::Parsed(_, len) | Entry::Peeked(Some(len)) | Entry::Seed { len, .. } => {
+                Some((true, *len))
+            }
+            Entry::Failed(_) | Entry::Peeked(None) => Some((false, 0)),
+            Entry::InProgress(recursed) => {
+                *recursed = true;
+                Some((false, 0))
+            }
+        }
+    }
+
+    /// Whether `P` was reached again while it was in progress
+    fn recursed<P: 'static>(&self, offset: usize) -> bool {
+        let entry = self
+            .entries
+            .get(&offset)
+            .and_then(|entries| entries.get(&TypeId::of::<P>()));
+        matches!(entry, Some(Entry::InProgress(true)))
+    }
+}
+
+fn left_recursion<P>(offset: usize) -> String {
+    format!(
+        "left recursion, `{}` was parsed again at offset {} without reading any input",
+        std::any::type_name::<P>(),
+        offset
+    )
+}
+
+/// Whether the [`Context`](crate::context::Context) of the input has limited the parse, so the result might differ if it was parsed again
+fn limited<T>(input: &mut impl Buffer<T>) -> bool {
+    input.context().map_or(false, |context| context.limited())
+}
+
+/// Runs `f` on a new cursor, returning what it returned and how far the cursor got
+fn run<T, B: Buffer<T>, R>(
+    input: &mut B,
+    f: &mut impl FnMut(&mut Cursor<T, B::CursorBase>) -> R,
+) -> (R, usize) {
+    let mut cursor = input.cursor();
+    let output = f(&mut cursor);
+    (output, cursor.position())
+}
+
+/// Grows the seed of a left recursive parse, calling `parse` again for as long as it reads further than the last attempt.
+///
+/// Each attempt is lent the last result through the memo instead of a copy of it, so that growing a long chain
+/// isn't quadratic. Returns `None` if an attempt took the seed but didn't grow it, since then the seed is lost
+fn grow<P, T, B>(
+    input: &mut B,
+    offset: usize,
+    mut seed: P,
+    mut len: usize,
+    parse: &mut impl FnMut(&mut Cursor<T, B::CursorBase>) -> eyre::Result<P>,
+) -> Option<(P, usize)>
+where
+    P: Clone + Send + Sync + 'static,
+    B: Buffer<T>,
+{
+    loop {
+        let memo = match input.memo() {
+            Some((_, memo)) => memo,
+            None => return Some((seed, len)),
+        };
+        let lent = Entry::Seed {
+            seed: Some(Box::new(seed)),
+            len,
+            missed: false,
+        };
+        memo.insert::<P>(offset, lent);
+
+        let (next, next_len) = run(input, parse);
+        let (unused, missed) = match input.memo() {
+            Some((_, memo)) => memo.take_seed::<P>(offset),
+            None => (None, true),
+        };
+        match (next, unused) {
+            _ if missed => return None,
+            (Ok(next), _) if next_len > len => {
+                seed = next;
+                len = next_len;
+            }
+            (_, Some(unused)) => return Some((unused, len)),
+            (_, None) => return None,
+        }
+    }
+}
+
+/// Grows the seed like [`grow`], but saves a copy of it for each attempt, so that it can be reached any number of times
+fn grow_copying<P, T, B>(
+    input: &mut B,
+    offset: usize,
+    mut seed: P,
+    mut len: usize,
+    parse: &mut impl FnMut(&mut Cursor<T, B::CursorBase>) -> eyre::Result<P>,
+) -> (P, usize)
+where
+    P: Clone + Send + Sync + 'static,
+    B: Buffer<T>,
+{
+    loop {
+        if let Some((_, memo)) = input.memo() {
+            memo.insert::<P>(offset, Entry::Parsed(Box::new(seed.clone()), len));
+        }
+        match run(input, parse) {
+            (Ok(next), next_len) if next_len > len => {
+                seed = next;
+                len = next_len;
+            }
+            _ => return (seed, len),
+        }
+    }
+}
+
+/// Parses `P` using `parse`, unless `P` has already been parsed at the same offset of the input.
+///
+/// If it has, the saved result is used instead. Saved errors only keep their message,
+/// so they lose any context chain they had. Results are not saved once the [`Context`](crate::context::Context)
+/// has interrupted the parse or it has nested too deep, since they depend on more than the offset.
+/// `parse` reads from a cursor, and the input is only moved forward if it succeeds.
+///
+/// If `parse` parses `P` again at the same offset, the inner attempt fails.
+/// Then if the outer attempt succeeds, its result is saved and `parse` is called again,
+/// for as long as it reads further than the last attempt.
+/// This is how directly left recursive rules, such as `Expr ::= Expr "+" Number | Number`, are parsed.
+/// Each attempt takes the last result the first time it parses `P` at that offset, rather than a copy of it,
+/// so an attempt should only parse `P` there if it's going to use it. Otherwise the growing starts again,
+/// copying the result for each attempt.
+///
+/// This is what `#[nommy(memoize)]` uses to implement [`Parse::parse`](crate::Parse::parse).
+/// Buffers without a [`Memo`] call `parse` once, so left recursive rules recurse forever
+///
+/// # Errors
+/// If `parse` fails, now or when it was first called at this offset
+pub fn parse<P, T, B>(
+    input: &mut B,
+    mut parse: impl FnMut(&mut Cursor<T, B::CursorBase>) -> eyre::Result<P>,
+) -> eyre::Result<P>
+where
+    P: Clone + Send + Sync + 'static,
+    B: Buffer<T>,
+{
+    let offset = if let Some((offset, memo)) = input.memo() {
+        match memo.parsed::<P>(offset) {
+            Some(Ok((p, len))) => {
+                input.fast_forward(len);
+                return Ok(p);
+            }
+            Some(Err(err)) => return Err(eyre::eyre!(err)),
+            None => {
+                memo.insert::<P>(offset, Entry::InProgress(false));
+                offset
+            }
+        }
+    } else {
+        let (result, len) = run(input, &mut parse);
+        if result.is_ok() {
+            input.fast_forward(len);
+        }
+        return result;
+    };
+
+    let (mut result, mut len) = run(input, &mut parse);
+    let recursed = input
+        .memo()
+        .map_or(false, |(_, memo)| memo.recursed::<P>(offset));
+    if recursed {
+        if let Ok(seed) = result {
+            let grown = if let Some(grown) = grow(input, offset, seed, len, &mut parse) {
+                Ok(grown)
+            } else {
+                // an attempt took the seed without growing it, so start again and copy the seed each time instead
+                if let Some((_, memo)) = input.memo() {
+                    memo.insert::<P>(offset, Entry::InProgress(false));
+                }
+                match run(input, &mut parse) {
+                    (Ok(seed), len) => Ok(grow_copying(input, offset, seed, len, &mut parse)),
+                    (Err(err), _) => Err(err),
+                }
+            };
+            result = grown.map(|(p, grown_len)| {
+                len = grown_len;
+                p
+            });
+        }
+    }
+
+    let limited = match &result {
+        Err(err) => err.is::<DepthExceeded>() || err.is::<Interrupted>() || limited(input),
+        Ok(_) => limited(input),
+    };
+    if let Some((_, memo)) = input.memo() {
+        let entry = match &result {
+            _ if limited => None,
+            Ok(p) => Some(Entry::Parsed(Box::new(p.clone()), len)),
+            Err(err) => Some(Entry::Failed(format!("{:#}", err))),
+        };
+        match entry {
+            Some(entry) => memo.insert::<P>(offset, entry),
+            None => memo.remove::<P>(offset),
+        }
+    }
+    if result.is_ok() {
+        input.fast_forward(len);
+    }
+    result
+}
+
+/// Peeks `P` using `peek`, unless `P` has already been parsed or peeked at the same offset of the input.
+///
+/// If it has, the saved result is used instead. Left recursion is handled the same way as [`parse`].
+/// This is what `#[nommy(memoize)]` uses to implement [`Parse::peek`](crate::Parse::peek)
+pub fn peek<P, T, B>(
+    input: &mut B,
+    mut peek: impl FnMut(&mut Cursor<T, B::CursorBase>) -> bool,
+) -> bool
+where
+    P: 'static,
+    B: Buffer<T>,
+{
+    let offset = if let Some((offset, memo)) = input.memo() {
+        if let Some((matched, len)) = memo.peeked::<P>(offset) {
+            input.fast_forward(len);
+            return matched;
+        }
+        memo.insert::<P>(offset, Entry::InProgress(false));
+        offset
+    } else {
+        let (matched, len) = run(input, &mut peek);
+        if matched {
+            input.fast_forward(len);
+        }
+        return matched;
+    };
+
+    let (matched, mut len) = run(input, &mut peek);
+    if let (true, Some((_, memo))) = (matched, input.memo()) {
+        if memo.recursed::<P>(offset) {
+            // grow the seed
+            loop {
+                if let Some((_, memo)) = input.memo() {
+                    memo.insert::<P>(offset, Entry::Peeked(Some(len)));
+                }
+                match run(input, &mut peek) {
+                    (true, next_len) if next_len > len => len = next_len,
+                    _ => break,
+                }
+            }
+        }
+    }
+
+    let limited = limited(input);
+    if let Some((_, memo)) = input.memo() {
+        if limited {
+            memo.remove::<P>(offset);
+        } else {
+            memo.insert::<P>(
+                offset,
+                Entry::Peeked(if matched { Some(len) } else { None }),
+            );
+        }
+    }
+    if matched {
+        input.fast_forward(len);
+    }
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{
+        context::{self, Context},
+        text::Tag,
+        Buf, IntoBuf, Parse, SliceBuf,
+    };
+
+    thread_local! {
+        static CALLS: Cell<usize> = Cell::new(0);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Foo;
+
+    impl Parse<char> for Foo {
+        fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+            parse(input, |input| {
+                CALLS.with(|c| c.set(c.get() + 1));
+                Tag::<"foo">::parse(input)?;
+                Ok(Self)
+            })
+        }
+
+        fn peek(input: &mut impl Buffer<char>) -> bool {
+            peek::<Self, _, _>(input, |input| {
+                CALLS.with(|c| c.set(c.get() + 1));
+                Tag::<"foo">::peek(input)
+            })
+        }
+    }
+
+    fn calls() -> usize {
+        CALLS.with(|c| c.replace(0))
+    }
+
+    #[test]
+    fn parse_once() {
+        let mut input = "foofoo".chars().into_buf();
+        for _ in 0..3 {
+            assert_eq!(Foo::parse(&mut input.cursor()).unwrap(), Foo);
+        }
+        assert!(Foo::peek(&mut input.cursor()));
+        assert_eq!(calls(), 1);
+
+        // the second foo is at a different offset
+        let mut cursor = input.cursor();
+        Foo::parse(&mut cursor).unwrap();
+        Foo::parse(&mut cursor).unwrap();
+        assert_eq!(cursor.next(), None);
+        assert_eq!(calls(), 1);
+    }
+
+    #[test]
+    fn errors() {
+        let mut input = SliceBuf::new(&['f', 'o', 'x']);
+        let err = Foo::parse(&mut input).unwrap_err().to_string();
+        assert_eq!(Foo::parse(&mut input).unwrap_err().to_string(), err);
+        assert!(!Foo::peek(&mut input.cursor()));
+        assert_eq!(calls(), 1);
+
+        // failed parses leave the input where it was
+        assert_eq!(input.remaining(), ['f', 'o', 'x']);
+    }
+
+    #[test]
+    fn peek_then_parse() {
+        let mut input = "foo".chars().into_buf();
+        assert!(Foo::peek(&mut input.cursor()));
+        assert!(Foo::peek(&mut input.cursor()));
+        assert_eq!(calls(), 1);
+
+        // peeking doesn't save the value, so it still has to be parsed
+        Foo::parse(&mut input).unwrap();
+        assert_eq!(calls(), 1);
+    }
+
+    #[test]
+    fn forgets_read_input() {
+        let mut input = "foofoo".chars().into_buf();
+        Foo::parse(&mut input).unwrap();
+        Foo::peek(&mut input.cursor());
+        assert_eq!(input.memo().unwrap().1.len(), 1);
+
+        input.fast_forward(3);
+        assert!(input.memo().unwrap().1.is_empty());
+    }
+
+    /// `foo`, one level deeper
+    #[derive(Debug, Clone, PartialEq)]
+    struct Nested;
+
+    impl Parse<char> for Nested {
+        fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+            parse(input, |input| {
+                context::parse(input, |input| {
+                    Tag::<"foo">::parse(input)?;
+                    Ok(Self)
+                })
+            })
+        }
+    }
+
+    #[test]
+    fn limits_are_not_saved() {
+        let mut input = Buf::new("foo".chars()).with_context(Context::new().max_depth(1));
+        let err = context::parse(&mut input.cursor(), Nested::parse).unwrap_err();
+        assert!(err.is::<DepthExceeded>());
+
+        // at the top level, `Nested` is within the max depth
+        assert_eq!(Nested::parse(&mut input).unwrap(), Nested);
+    }
+
+    /// `Sum ::= Sum "+" Digit | Digit`
+    #[derive(Debug, Clone, PartialEq)]
+    enum Sum {
+        Add(Box<Self>, char),
+        Digit(char),
+    }
+
+    impl Parse<char> for Sum {
+        fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+            parse(input, |input| {
+                let mut cursor = input.cursor();
+                if let Ok(lhs) = Self::parse(&mut cursor) {
+                    if cursor.next() == Some('+') {
+                        if let Some(digit) = cursor.next().filter(char::is_ascii_digit) {
+                            let pos = cursor.position();
+                            input.fast_forward(pos);
+                            return Ok(Self::Add(Box::new(lhs), digit));
+                        }
+                    }
+                }
+                match input.next() {
+                    Some(digit) if digit.is_ascii_digit() => Ok(Self::Digit(digit)),
+                    _ => Err(eyre::eyre!("expected a digit")),
+                }
+            })
+        }
+
+        fn peek(input: &mut impl Buffer<char>) -> bool {
+            peek::<Self, _, _>(input, |input| {
+                let mut cursor = input.cursor();
+                if Self::peek(&mut cursor)
+                    && cursor.next() == Some('+')
+                    && cursor.next().map_or(false, |c| c.is_ascii_digit())
+                {
+                    let pos = cursor.position();
+                    input.fast_forward(pos);
+                    return true;
+                }
+                input.next().map_or(false, |c| c.is_ascii_digit())
+            })
+        }
+    }
+
+    #[test]
+    fn left_recursion() {
+        let mut input = SliceBuf::new(&['1', '+', '2', '+', '3', '+']);
+        assert!(Sum::peek(&mut input.cursor()));
+        let mut cursor = input.cursor();
+        assert!(Sum::peek(&mut cursor));
+        assert_eq!(cursor.position(), 5);
+
+        let sum = Sum::parse(&mut input).unwrap();
+        let one_two = Sum::Add(Box::new(Sum::Digit('1')), '2');
+        assert_eq!(sum, Sum::Add(Box::new(one_two), '3'));
+        assert_eq!(input.remaining(), ['+']);
+    }
+
+    /// `Loop ::= Loop "a"`, which can never match
+    #[derive(Debug, Clone)]
+    struct Loop;
+
+    impl Parse<char> for Loop {
+        fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+            parse(input, |input| {
+                Self::parse(input)?;
+                Tag::<"a">::parse(input)?;
+                Ok(Self)
+            })
+        }
+
+        fn peek(input: &mut impl Buffer<char>) -> bool {
+            peek::<Self, _, _>(input, |input| Self::peek(input) && Tag::<"a">::peek(input))
+        }
+    }
+
+    #[test]
+    fn left_recursion_without_seed() {
+        let mut input = "aaa".chars().into_buf();
+        assert!(!Loop::peek(&mut input.cursor()));
+        let err = Loop::parse(&mut input.cursor()).unwrap_err().to_string();
+        assert!(err.starts_with("left recursion"), "{}", err);
+    }
+}
@@ -0,0 +1,340 @@
+//! Operator precedence parsing, for expressions built out of prefix, infix and postfix operators.
+//!
+//! A type describes its operators by implementing [`Pratt`], and is then parsed with [`parse`].
+//! Enums can derive it by marking their operator variants with `infix`, `postfix` or `prefix`,
+//! along with a `precedence`. Every other variant is parsed as an operand
+//!
+//! ```
+//! use nommy::{parse, text::*, Parse};
+//!
+//! #[derive(Debug, PartialEq, Parse)]
+//! #[nommy(parse_type = char)]
+//! enum Expr {
+//!     #[nommy(infix = Tag<"+">, precedence = 1)]
+//!     Add(Box<Expr>, Box<Expr>),
+//!     #[nommy(infix = Tag<"*">, precedence = 2)]
+//!     Mul(Box<Expr>, Box<Expr>),
+//!     #[nommy(infix = Tag<"^">, precedence = 3, assoc = "right")]
+//!     Pow(Box<Expr>, Box<Expr>),
+//!     #[nommy(prefix = Tag<"-">, precedence = 4)]
+//!     Neg(Box<Expr>),
+//!     Num(#[nommy(parser = AnyOf1<"0123456789">)] String),
+//! }
+//!
+//! fn num(n: &str) -> Box<Expr> {
+//!     Box::new(Expr::Num(n.to_string()))
+//! }
+//!
+//! let expr: Expr = parse("1+2*-3".chars()).unwrap();
+//! assert_eq!(expr, Expr::Add(num("1"), Box::new(Expr::Mul(num("2"), Box::new(Expr::Neg(num("3")))))));
+//!
+//! let expr: Expr = parse("1^2^3".chars()).unwrap();
+//! assert_eq!(expr, Expr::Pow(num("1"), Box::new(Expr::Pow(num("2"), num("3")))));
+//! ```
+
+use crate::{context, eyre, Buffer, Cursor};
+
+/// How an infix operator groups with other operators of the same precedence
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Assoc {
+    /// `a - b - c` is `(a - b) - c`
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`
+    Right,
+}
+
+/// Describes the operators of an expression type, so it can be parsed with [`parse`].
+///
+/// Operators with a higher precedence bind tighter. The operator methods are given a cursor,
+/// so they can read as much as they need to and return `None` if there's no operator there
+///
+/// ```
+/// use nommy::{eyre, pratt::{self, Assoc, Pratt}, text::AnyOf1, Buffer, Parse, SliceBuf};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Expr {
+///     Num(u64),
+///     Binary(char, Box<Expr>, Box<Expr>),
+///     Factorial(Box<Expr>),
+/// }
+///
+/// impl Pratt<char> for Expr {
+///     type Op = char;
+///
+///     fn atom(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+///         let digits: String = AnyOf1::<"0123456789">::parse(input)?.into();
+///         Ok(Expr::Num(digits.parse()?))
+///     }
+///
+///     fn infix(input: &mut impl Buffer<char>) -> Option<(char, u32, Assoc)> {
+///         match input.next()? {
+///             op @ '+' | op @ '-' => Some((op, 1, Assoc::Left)),
+///             op @ '*' | op @ '/' => Some((op, 2, Assoc::Left)),
+///             _ => None,
+///         }
+///     }
+///
+///     fn postfix(input: &mut impl Buffer<char>) -> Option<(char, u32)> {
+///         input.next_if(|&c| c == '!').map(|op| (op, 3))
+///     }
+///
+///     fn unary(_: char, operand: Self) -> Self {
+///         Expr::Factorial(Box::new(operand))
+///     }
+///
+///     fn binary(op: char, lhs: Self, rhs: Self) -> Self {
+///         Expr::Binary(op, Box::new(lhs), Box::new(rhs))
+///     }
+/// }
+///
+/// let expr: Expr = pratt::parse(&mut SliceBuf::new(&['1', '-', '2', '*', '3', '!'])).unwrap();
+/// let product = Expr::Binary('*', Box::new(Expr::Num(2)), Box::new(Expr::Factorial(Box::new(Expr::Num(3)))));
+/// assert_eq!(expr, Expr::Binary('-', Box::new(Expr::Num(1)), Box::new(product)));
+/// ```
+pub trait Pratt<T>: Sized {
+    /// The operators, as they're read from the input
+    type Op;
+
+    /// Parses an operand that doesn't start with an operator, such as a number or a bracketed expression
+    ///
+    /// # Errors
+    /// If there's no operand
+    fn atom(input: &mut impl Buffer<T>) -> eyre::Result<Self>;
+
+    /// Peeks an operand, see [`Pratt::atom`]. Defaults to parsing it and throwing it away
+    fn peek_atom(input: &mut impl Buffer<T>) -> bool {
+        Self::atom(input).is_ok()
+    }
+
+    /// Reads a prefix operator, returning it with its precedence
+    fn prefix(_input: &mut impl Buffer<T>) -> Option<(Self::Op, u32)> {
+        None
+    }
+
+    /// Reads an infix operator, returning it with its precedence and associativity
+    fn infix(_input: &mut impl Buffer<T>) -> Option<(Self::Op, u32, Assoc)> {
+        None
+    }
+
+    /// Reads a postfix operator, returning it with its precedence
+    fn postfix(_input: &mut impl Buffer<T>) -> Option<(Self::Op, u32)> {
+        None
+    }
+
+    /// Builds the expression for a prefix or postfix operator
+    fn unary(op: Self::Op, operand: Self) -> Self;
+
+    /// Builds the expression for an infix operator
+    fn binary(op: Self::Op, lhs: Self, rhs: Self) -> Self;
+}
+
+/// Parses an expression of `P`, using [`Pratt`] to find its operands and operators.
+/// The derived [`Parse::parse`](crate::Parse::parse) of an enum with operators calls this
+///
+/// # Errors
+/// If an operand is missing, including after an infix or prefix operator
+pub fn parse<P: Pratt<T>, T>(input: &mut impl Buffer<T>) -> eyre::Result<P> {
+    parse_power(input, 0)
+}
+
+/// Peeks an expression of `P`, accepting the same input as [`parse`].
+/// The derived [`Parse::peek`](crate::Parse::peek) of an enum with operators calls this
+pub fn peek<P: Pratt<T>, T>(input: &mut impl Buffer<T>) -> bool {
+    peek_power::<P, T>(input, 0)
+}
+
+/// The binding power of an operator. Doubled so that there's room in between
+/// for the operand of a left associative or prefix operator
+fn power(precedence: u32) -> u64 {
+    u64::from(precedence) * 2
+}
+
+/// The binding power that the operand to the right of an operator must exceed
+fn operand_power(precedence: u32, assoc: Assoc) -> u64 {
+    match assoc {
+        Assoc::Left => power(precedence) + 1,
+        Assoc::Right => power(precedence),
+    }
+}
+
+/// Reads an operator on a cursor, only moving the input past it if `binds` accepts it
+fn operator<T, B: Buffer<T>, O>(
+    input: &mut B,
+    read: impl FnOnce(&mut Cursor<T, B::CursorBase>) -> Option<O>,
+    binds: impl FnOnce(&O) -> bool,
+) -> Option<O> {
+    let mut cursor = input.cursor();
+    let op = read(&mut cursor).filter(binds)?;
+    let pos = cursor.position();
+    input.fast_forward(pos);
+    Some(op)
+}
+
+/// Parses an expression made of operators with a binding power of at least `min`
+fn parse_power<P: Pratt<T>, T>(input: &mut impl Buffer<T>, min: u64) -> eyre::Result<P> {
+    let mut lhs = match operator(input, |input| P::prefix(input), |_| true) {
+        Some((op, precedence)) => {
+            let power = operand_power(precedence, Assoc::Left);
+            let operand = context::parse(input, |input| parse_power(input, power))?;
+            P::unary(op, operand)
+        }
+        None => P::atom(input)?,
+    };
+
+    loop {
+        let postfix = operator(
+            input,
+            |input| P::postfix(input),
+            |(_, precedence)| power(*precedence) >= min,
+        );
+        if let Some((op, _)) = postfix {
+            lhs = P::unary(op, lhs);
+            continue;
+        }
+
+        let infix = operator(
+            input,
+            |input| P::infix(input),
+            |(_, precedence, _)| power(*precedence) >= min,
+        );
+        match infix {
+            Some((op, precedence, assoc)) => {
+                let power = operand_power(precedence, assoc);
+                let rhs = context::parse(input, |input| parse_power(input, power))?;
+                lhs = P::binary(op, lhs, rhs);
+            }
+            None => return Ok(lhs),
+        }
+    }
+}
+
+/// Peeks the same input as [`parse_power`]
+fn peek_power<P: Pratt<T>, T>(input: &mut impl Buffer<T>, min: u64) -> bool {
+    let operand = match operator(input, |input| P::prefix(input), |_| true) {
+        Some((_, precedence)) => {
+            let power = operand_power(precedence, Assoc::Left);
+            context::peek(input, |input| peek_power::<P, T>(input, power))
+        }
+        None => P::peek_atom(input),
+    };
+    if !operand {
+        return false;
+    }
+
+    loop {
+        let postfix = operator(
+            input,
+            |input| P::postfix(input),
+            |(_, precedence)| power(*precedence) >= min,
+        );
+        if postfix.is_some() {
+            continue;
+        }
+
+        let infix = operator(
+            input,
+            |input| P::infix(input),
+            |(_, precedence, _)| power(*precedence) >= min,
+        );
+        match infix {
+            Some((_, precedence, assoc)) => {
+                let power = operand_power(precedence, assoc);
+                if !context::peek(input, |input| peek_power::<P, T>(input, power)) {
+                    return false;
+                }
+            }
+            None => return true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntoBuf;
+
+    /// Single digit numbers, with `-` as a prefix and infix operator, `^` and `!`
+    #[derive(Debug, PartialEq)]
+    enum Expr {
+        Num(u32),
+        Unary(char, Box<Expr>),
+        Binary(char, Box<Expr>, Box<Expr>),
+    }
+
+    impl Pratt<char> for Expr {
+        type Op = char;
+
+        fn atom(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+            let digit = input.next().and_then(|c| c.to_digit(10));
+            digit
+                .map(Expr::Num)
+                .ok_or_else(|| eyre::eyre!("expected a digit"))
+        }
+
+        fn prefix(input: &mut impl Buffer<char>) -> Option<(char, u32)> {
+            input.next_if(|&c| c == '-').map(|op| (op, 3))
+        }
+
+        fn infix(input: &mut impl Buffer<char>) -> Option<(char, u32, Assoc)> {
+            match input.next()? {
+                '-' => Some(('-', 1, Assoc::Left)),
+                '^' => Some(('^', 2, Assoc::Right)),
+                _ => None,
+            }
+        }
+
+        fn postfix(input: &mut impl Buffer<char>) -> Option<(char, u32)> {
+            input.next_if(|&c| c == '!').map(|op| (op, 4))
+        }
+
+        fn unary(op: char, operand: Self) -> Self {
+            Self::Unary(op, Box::new(operand))
+        }
+
+        fn binary(op: char, lhs: Self, rhs: Self) -> Self {
+            Self::Binary(op, Box::new(lhs), Box::new(rhs))
+        }
+    }
+
+    /// Writes the expression out with brackets around every operator
+    fn show(expr: &Expr) -> String {
+        match expr {
+            Expr::Num(n) => n.to_string(),
+            Expr::Unary('!', operand) => format!("({}!)", show(operand)),
+            Expr::Unary(op, operand) => format!("({}{})", op, show(operand)),
+            Expr::Binary(op, lhs, rhs) => format!("({}{}{})", show(lhs), op, show(rhs)),
+        }
+    }
+
+    fn parse_str(s: &str) -> eyre::Result<String> {
+        let mut input = s.chars().into_buf();
+        assert_eq!(
+            peek::<Expr, _>(&mut input.cursor()),
+            parse::<Expr, _>(&mut input.cursor()).is_ok()
+        );
+        let expr = parse::<Expr, _>(&mut input)?;
+        Ok(show(&expr) + &input.collect::<String>())
+    }
+
+    #[test]
+    fn associativity() {
+        assert_eq!(parse_str("1-2-3").unwrap(), "((1-2)-3)");
+        assert_eq!(parse_str("1^2^3").unwrap(), "(1^(2^3))");
+        assert_eq!(parse_str("1-2^3-4").unwrap(), "((1-(2^3))-4)");
+    }
+
+    #[test]
+    fn prefix_and_postfix() {
+        assert_eq!(parse_str("--1").unwrap(), "(-(-1))");
+        assert_eq!(parse_str("-1-2").unwrap(), "((-1)-2)");
+        assert_eq!(parse_str("-1!").unwrap(), "(-(1!))");
+        assert_eq!(parse_str("1!!^2").unwrap(), "(((1!)!)^2)");
+    }
+
+    #[test]
+    fn stops_at_unknown_input() {
+        assert_eq!(parse_str("1-2)").unwrap(), "(1-2))");
+        assert!(parse_str("1-").is_err());
+        assert!(parse_str("-").is_err());
+    }
+}
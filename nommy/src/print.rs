@@ -0,0 +1,153 @@
+use crate::Vec1;
+
+/// The inverse of [`Parse`](crate::Parse). Writes a value back out in a form that parses into the same value
+///
+/// ```
+/// use nommy::{parse, print, text::{AnyOf1, Tag}, Print};
+/// let word: AnyOf1<"abc"> = parse("abba".chars()).unwrap();
+/// let tag: Tag<"!"> = Tag;
+///
+/// let mut output = String::new();
+/// word.print(&mut output);
+/// tag.print(&mut output);
+/// assert_eq!(output, "abba!");
+/// ```
+pub trait Print<T> {
+    /// Writes self to the end of output
+    fn print(&self, output: &mut impl Extend<T>);
+}
+
+/// `print` collects the output of [`P::print`](Print::print) into a new collection, usually a
+/// [`String`] or `Vec<u8>`
+///
+/// ```
+/// use nommy::{print, text::Tag};
+/// let tags: Vec<Tag<"ab">> = vec![Tag, Tag];
+/// let s: String = print(&tags);
+/// assert_eq!(s, "abab");
+/// ```
+#[must_use]
+pub fn print<T, P: Print<T>, O: Default + Extend<T>>(value: &P) -> O {
+    let mut output = O::default();
+    value.print(&mut output);
+    output
+}
+
+/// Prints the default value of `P`.
+/// Used by `#[derive(Print)]` to write out prefixes, suffixes, separators and ignored values
+pub fn print_default<P: Default + Print<T>, T>(output: &mut impl Extend<T>) {
+    P::default().print(output);
+}
+
+/// Prints `value` with the parser `P` that it was parsed by, converting it back into a `P` first.
+/// Used by `#[derive(Print)]` to write out fields with a `parser` or `inner_parser`
+///
+/// ```
+/// use nommy::{print_with, bytes::BigEndian};
+/// let mut output = Vec::new();
+/// print_with::<BigEndian<u16>, _, _>(&0x1234_u16, &mut output);
+/// assert_eq!(output, [0x12, 0x34]);
+/// ```
+pub fn print_with<P: From<V> + Print<T>, V: Clone, T>(value: &V, output: &mut impl Extend<T>) {
+    P::from(value.clone()).print(output);
+}
+
+/// Prints `P` if it is present, nothing otherwise
+impl<P: Print<T>, T> Print<T> for Option<P> {
+    fn print(&self, output: &mut impl Extend<T>) {
+        if let Some(p) = self {
+            p.print(output);
+        }
+    }
+}
+
+/// Prints each `P` in order
+impl<P: Print<T>, T> Print<T> for Vec<P> {
+    fn print(&self, output: &mut impl Extend<T>) {
+        for p in self {
+            p.print(output);
+        }
+    }
+}
+
+/// Prints each `P` in order
+impl<P: Print<T>, T> Print<T> for Vec1<P> {
+    fn print(&self, output: &mut impl Extend<T>) {
+        self.as_ref().print(output);
+    }
+}
+
+/// Prints each `P` in order
+impl<P: Print<T>, T, const N: usize> Print<T> for [P; N] {
+    fn print(&self, output: &mut impl Extend<T>) {
+        for p in self {
+            p.print(output);
+        }
+    }
+}
+
+impl<P: Print<T>, T> Print<T> for Box<P> {
+    fn print(&self, output: &mut impl Extend<T>) {
+        self.as_ref().print(output);
+    }
+}
+
+impl Print<char> for char {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(Some(*self));
+    }
+}
+
+impl Print<char> for String {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(self.chars());
+    }
+}
+
+impl Print<u8> for u8 {
+    fn print(&self, output: &mut impl Extend<u8>) {
+        output.extend(Some(*self));
+    }
+}
+
+macro_rules! print_display {
+    ($($t:ty),*) => {$(
+        /// Prints the number in decimal
+        impl Print<char> for $t {
+            fn print(&self, output: &mut impl Extend<char>) {
+                output.extend(self.to_string().chars());
+            }
+        }
+    )*};
+}
+
+print_display!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, text::Tag, Vec1};
+
+    #[test]
+    fn containers() {
+        let s: String = print(&Some(Tag::<"a">));
+        assert_eq!(s, "a");
+        let s: String = print(&None::<Tag<"a">>);
+        assert_eq!(s, "");
+
+        let v: Vec1<Tag<"ab">> = parse("abab".chars()).unwrap();
+        let s: String = print(&v);
+        assert_eq!(s, "abab");
+
+        let s: String = print(&[Box::new('x'), Box::new('y')]);
+        assert_eq!(s, "xy");
+    }
+
+    #[test]
+    fn numbers() {
+        let s: String = print(&(-123_i32));
+        assert_eq!(s, "-123");
+        let b: Vec<u8> = print::<u8, _, _>(&vec![1_u8, 2, 3]);
+        assert_eq!(b, [1, 2, 3]);
+    }
+}
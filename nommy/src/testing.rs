@@ -0,0 +1,443 @@
+//! Helpers for testing parsers from `#[test]` functions.
+//!
+//! [`RoundTrip`] checks that values survive being [printed](crate::Print) and
+//! [parsed](crate::Parse) again, for as many values as you can generate.
+//! Everything runs from a seeded [`Rng`], so a failure is reproduced by
+//! running the same test again.
+
+use crate::{parse_terminated, print, Parse, Print};
+use std::fmt::Debug;
+
+/// A small, seeded pseudo random number generator (splitmix64).
+/// Not suitable for anything but generating test cases
+///
+/// ```
+/// use nommy::testing::Rng;
+/// let mut a = Rng::new(42);
+/// let mut b = Rng::new(42);
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// assert!(a.below(10) < 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+    /// Values to return instead of generating them, so that a failing case can be replayed with changes.
+    /// Once they run out, every value is 0
+    replay: Option<Vec<u64>>,
+    /// Every value returned so far, if they're being recorded
+    record: Option<Vec<u64>>,
+}
+
+impl Rng {
+    /// Creates a new Rng from the given seed
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            replay: None,
+            record: None,
+        }
+    }
+
+    /// A seeded Rng that records the values it returns
+    const fn recording(seed: u64) -> Self {
+        Self {
+            state: seed,
+            replay: None,
+            record: Some(Vec::new()),
+        }
+    }
+
+    /// An Rng that returns `values` in order, then 0, recording the values it returns
+    const fn replaying(values: Vec<u64>) -> Self {
+        Self {
+            state: 0,
+            replay: Some(values),
+            record: Some(Vec::new()),
+        }
+    }
+
+    /// Returns the next random `u64`
+    pub fn next_u64(&mut self) -> u64 {
+        let value = if let (Some(values), Some(record)) = (&self.replay, &self.record) {
+            values.get(record.len()).copied().unwrap_or(0)
+        } else {
+            self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = self.state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        if let Some(record) = &mut self.record {
+            record.push(value);
+        }
+        value
+    }
+
+    /// Returns a random number in `0..n`, or 0 if `n` is 0
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+        let value = self.next_u64() % n as u64;
+        self.recorded(value) as usize
+    }
+
+    /// Returns true half of the time
+    pub fn gen_bool(&mut self) -> bool {
+        let value = self.next_u64() & 1;
+        self.recorded(value) == 1
+    }
+
+    /// Records `value` in place of the last value returned, which it was derived from.
+    /// Then shrinking works on the numbers that were actually used
+    fn recorded(&mut self, value: u64) -> u64 {
+        if let Some(last) = self.record.as_mut().and_then(|record| record.last_mut()) {
+            *last = value;
+        }
+        value
+    }
+
+    /// Picks a random element out of `items`
+    ///
+    /// # Panics
+    /// If `items` is empty
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// Checks that `parse_terminated(print(v)) == v` for many generated values.
+///
+/// The generator is given an [`Rng`] and a size hint, which grows from 0 up to
+/// [`max_size`](RoundTrip::max_size) over the run. When a value fails, it's shrunk
+/// by replaying the random values that generated it with changes, keeping each change
+/// that still fails: a smaller size, fewer values, or smaller values, which make
+/// [`Rng::below`] and [`Rng::choose`] pick earlier options. The smallest failing value
+/// is reported in the panic message, along with the seed and size of the original failure.
+///
+/// ```
+/// use nommy::{testing::RoundTrip, text::*, Parse, Print};
+///
+/// #[derive(Debug, Parse, Print, PartialEq)]
+/// #[nommy(ignore = WhiteSpace)]
+/// struct List {
+///     #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+///     #[nommy(inner_parser = AnyOf1<"0123456789">, seperated_by = Tag<",">)]
+///     items: Vec<usize>,
+/// }
+///
+/// RoundTrip::new().check(|rng, size| List {
+///     items: (0..rng.below(size + 1)).map(|_| rng.below(1000)).collect(),
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct RoundTrip {
+    seed: u64,
+    cases: usize,
+    max_size: usize,
+    shrink_attempts: usize,
+}
+
+impl Default for RoundTrip {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            cases: 256,
+            max_size: 32,
+            shrink_attempts: 1024,
+        }
+    }
+}
+
+/// A value that didn't survive the round trip
+struct Failure<P> {
+    size: usize,
+    /// The values the generator took from the [`Rng`]
+    values: Vec<u64>,
+    value: P,
+    printed: String,
+    parsed: eyre::Result<P>,
+}
+
+impl<P> Failure<P> {
+    /// Whether this was generated from a smaller size, fewer values, or smaller values than `other`.
+    /// Shrinking only keeps simpler failures, so it always finishes
+    fn simpler_than(&self, other: &Self) -> bool {
+        (self.size, self.values.len(), &self.values)
+            < (other.size, other.values.len(), &other.values)
+    }
+}
+
+impl RoundTrip {
+    /// Creates a new `RoundTrip` with the default settings:
+    /// seed 0, 256 cases, up to size 32
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the seed that all cases are generated from
+    #[must_use]
+    pub const fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets how many values to generate
+    #[must_use]
+    pub const fn cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// Sets the largest size hint passed to the generator
+    #[must_use]
+    pub const fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Sets the most times a failing value is generated again while shrinking it
+    #[must_use]
+    pub const fn shrink_attempts(mut self, shrink_attempts: usize) -> Self {
+        self.shrink_attempts = shrink_attempts;
+        self
+    }
+
+    /// Runs the check against values made by `gen`
+    ///
+    /// # Panics
+    /// If any generated value doesn't print and parse back into itself.
+    /// The message contains the smallest failing value that was found
+    pub fn check<P, G>(&self, mut gen: G)
+    where
+        P: Parse<char> + Print<char> + PartialEq + Debug,
+        G: FnMut(&mut Rng, usize) -> P,
+    {
+        let mut seeds = Rng::new(self.seed);
+        for case in 0..self.cases {
+            let size = case * (self.max_size + 1) / self.cases.max(1);
+            let seed = seeds.next_u64();
+            if let Some(failure) = Self::run(&mut gen, Rng::recording(seed), size) {
+                let failure = self.shrink(&mut gen, failure);
+                panic!(
+                    "round trip failed after {} cases (seed {}, size {}), shrunk to size {}\n  value: {:?}\n  printed: {:?}\n  parsed: {:?}",
+                    case + 1,
+                    seed,
+                    size,
+                    failure.size,
+                    failure.value,
+                    failure.printed,
+                    failure.parsed,
+                );
+            }
+        }
+    }
+
+    /// Generates a value using `rng` and round trips it, returning the failure if it didn't survive
+    fn run<P, G>(gen: &mut G, mut rng: Rng, size: usize) -> Option<Failure<P>>
+    where
+        P: Parse<char> + Print<char> + PartialEq,
+        G: FnMut(&mut Rng, usize) -> P,
+    {
+        let value = gen(&mut rng, size);
+        let printed: String = print(&value);
+        let parsed = parse_terminated::<P, _>(printed.chars());
+        match &parsed {
+            Ok(p) if *p == value => None,
+            _ => Some(Failure {
+                size,
+                values: rng.record.unwrap_or_default(),
+                value,
+                printed,
+                parsed,
+            }),
+        }
+    }
+
+    /// Shrinks `best` as far as it goes, or until it's been generated again `shrink_attempts` times
+    fn shrink<P, G>(&self, gen: &mut G, mut best: Failure<P>) -> Failure<P>
+    where
+        P: Parse<char> + Print<char> + PartialEq,
+        G: FnMut(&mut Rng, usize) -> P,
+    {
+        let mut attempts = 0;
+        let mut shrunk = true;
+        while shrunk && attempts < self.shrink_attempts {
+            shrunk = false;
+
+            for size in 0..best.size {
+                let values = best.values.clone();
+                if let Some(failure) = self.attempt(gen, &mut attempts, &best, values, size) {
+                    best = failure;
+                    shrunk = true;
+                    break;
+                }
+            }
+
+            // removing values often removes part of the value, so try with a size that's smaller by as much too
+            for &len in &[8, 4, 2, 1] {
+                let mut i = 0;
+                while i + len <= best.values.len() {
+                    let mut values = best.values.clone();
+                    values.drain(i..i + len);
+                    let smaller = best.size.saturating_sub(len);
+                    let failure = self
+                        .attempt(gen, &mut attempts, &best, values.clone(), smaller)
+                        .or_else(|| self.attempt(gen, &mut attempts, &best, values, best.size));
+                    if let Some(failure) = failure {
+                        best = failure;
+                        shrunk = true;
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+
+            // binary search for the smallest value that still fails
+            let mut i = 0;
+            while i < best.values.len() {
+                let (mut low, mut high) = (0, best.values[i]);
+                while low < high && i < best.values.len() {
+                    let mid = low + (high - low) / 2;
+                    let mut values = best.values.clone();
+                    values[i] = mid;
+                    if let Some(failure) =
+                        self.attempt(gen, &mut attempts, &best, values, best.size)
+                    {
+                        best = failure;
+                        shrunk = true;
+                        high = mid;
+                    } else {
+                        low = mid + 1;
+                    }
+                }
+                i += 1;
+            }
+        }
+        best
+    }
+
+    /// Generates a value again from `values` at `size`, returning it if it fails and is simpler than `best`
+    fn attempt<P, G>(
+        &self,
+        gen: &mut G,
+        attempts: &mut usize,
+        best: &Failure<P>,
+        values: Vec<u64>,
+        size: usize,
+    ) -> Option<Failure<P>>
+    where
+        P: Parse<char> + Print<char> + PartialEq,
+        G: FnMut(&mut Rng, usize) -> P,
+    {
+        if *attempts >= self.shrink_attempts {
+            return None;
+        }
+        *attempts += 1;
+        Self::run(gen, Rng::replaying(values), size).filter(|failure| failure.simpler_than(best))
+    }
+}
+
+/// Shorthand for [`RoundTrip::new().check(gen)`](RoundTrip::check)
+///
+/// # Panics
+/// If any generated value doesn't print and parse back into itself
+pub fn check_round_trip<P, G>(gen: G)
+where
+    P: Parse<char> + Print<char> + PartialEq + Debug,
+    G: FnMut(&mut Rng, usize) -> P,
+{
+    RoundTrip::new().check(gen);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::AnyOf1;
+
+    #[test]
+    fn rng_is_seeded() {
+        let a: Vec<_> = (0..8)
+            .scan(Rng::new(1), |r, _| Some(r.next_u64()))
+            .collect();
+        let b: Vec<_> = (0..8)
+            .scan(Rng::new(1), |r, _| Some(r.next_u64()))
+            .collect();
+        let c: Vec<_> = (0..8)
+            .scan(Rng::new(2), |r, _| Some(r.next_u64()))
+            .collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn round_trip_passes() {
+        check_round_trip(|rng, size| {
+            let s: String = (0..=size).map(|_| *rng.choose(&['a', 'b'])).collect();
+            AnyOf1::<"ab">::parse(&mut crate::IntoBuf::into_buf(s.chars())).unwrap()
+        });
+    }
+
+    #[test]
+    fn round_trip_reports_smallest_failure() {
+        // two words next to each other print as one longer word
+        for seed in 0..4 {
+            let result = std::panic::catch_unwind(|| {
+                RoundTrip::new().seed(seed).check(|rng, size| {
+                    let words: Vec<AnyOf1<"a">> = (0..rng.below(size + 1))
+                        .map(|_| crate::parse("a".repeat(rng.below(4) + 1).chars()).unwrap())
+                        .collect();
+                    words
+                });
+            });
+            let err = result.unwrap_err();
+            let message = err.downcast_ref::<String>().unwrap();
+            assert!(
+                message.contains("value: [AnyOf1(\"a\"), AnyOf1(\"a\")]"),
+                "{}",
+                message
+            );
+            assert!(message.contains("printed: \"aa\""), "{}", message);
+        }
+    }
+
+    #[test]
+    fn shrinks_the_failing_value() {
+        // any word with a `b` in it fails, however long it is, but short words rarely have one
+        for seed in 0..4 {
+            let result = std::panic::catch_unwind(|| {
+                RoundTrip::new()
+                    .seed(seed)
+                    .max_size(256)
+                    .check(|rng, size| {
+                        let word = (0..=size)
+                            .map(|_| if rng.below(100) == 99 { 'b' } else { 'a' })
+                            .collect();
+                        Flaky(word)
+                    });
+            });
+            let err = result.unwrap_err();
+            let message = err.downcast_ref::<String>().unwrap();
+            assert!(message.contains("value: Flaky(\"b\")"), "{}", message);
+        }
+    }
+
+    /// Words that lose their `b`s when printed
+    #[derive(Debug, PartialEq)]
+    struct Flaky(String);
+
+    impl Parse<char> for Flaky {
+        fn parse(input: &mut impl crate::Buffer<char>) -> eyre::Result<Self> {
+            Ok(Self(AnyOf1::<"ab">::parse(input)?.into()))
+        }
+    }
+
+    impl Print<char> for Flaky {
+        fn print(&self, output: &mut impl Extend<char>) {
+            output.extend(self.0.chars().map(|c| if c == 'b' { 'a' } else { c }));
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use std::{convert::TryFrom, str::FromStr};
 
-use crate::{eyre, Buffer, Parse};
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    testing::Rng,
+    Buffer, Describe, Generate, Generator, Parse, Print, Vec1,
+};
 
 use super::OneOf;
 
@@ -21,33 +26,44 @@ impl<const CHARS: &'static str> From<AnyOf<CHARS>> for String {
     }
 }
 
-impl<const CHARS: &'static str> Parse<char> for AnyOf<CHARS> {
-    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        let mut output = String::new();
+impl<const CHARS: &'static str> From<String> for AnyOf<CHARS> {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
 
-        loop {
-            let mut cursor = input.cursor();
-            match OneOf::<CHARS>::parse(&mut cursor) {
-                Ok(c) => output.push(c.into()),
-                Err(_) => break,
-            }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
+impl<const CHARS: &'static str> Print<char> for AnyOf<CHARS> {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(self.0.chars());
+    }
+}
 
-        Ok(Self(output))
+impl<const CHARS: &'static str> Generate<char> for AnyOf<CHARS> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<char>) {
+        Vec::<OneOf<CHARS>>::generate(gen, output);
+    }
+}
+
+impl<const CHARS: &'static str> Describe<char> for AnyOf<CHARS> {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        Rule::repeat(OneOf::<CHARS>::describe(grammar), 0)
+    }
+}
+
+impl<const CHARS: &'static str> Parse<char> for AnyOf<CHARS> {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            let mut output = String::new();
+            input.read_while(|&c| OneOf::<CHARS>::contains(c), &mut output);
+            Ok(Self(output))
+        })
     }
 
     fn peek(input: &mut impl Buffer<char>) -> bool {
-        loop {
-            let mut cursor = input.cursor();
-            if !OneOf::<CHARS>::peek(&mut cursor) {
-                break;
-            }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
-        true
+        crate::context::check_peek(input, |input| {
+            input.advance_while(|&c| OneOf::<CHARS>::contains(c));
+            true
+        })
     }
 }
 
@@ -68,37 +84,79 @@ impl<const CHARS: &'static str> From<WhileNot1<CHARS>> for String {
     }
 }
 
-impl<const CHARS: &'static str> Parse<char> for WhileNot1<CHARS> {
-    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        let mut output = String::new();
+impl<const CHARS: &'static str> From<String> for WhileNot1<CHARS> {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
 
-        while !OneOf::<CHARS>::peek(&mut input.cursor()) {
-            match input.next() {
-                None => break,
-                Some(c) => output.push(c),
-            }
-        }
+impl<const CHARS: &'static str> Print<char> for WhileNot1<CHARS> {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(self.0.chars());
+    }
+}
 
-        if output.is_empty() {
-            Err(eyre::eyre!("no characters found"))
-        } else {
-            Ok(Self(output))
+/// Generates ASCII letters that aren't in `CHARS`, or any other printable ASCII character if every letter is.
+/// If every printable ASCII character is in `CHARS` too, any other `char` that isn't is generated
+impl<const CHARS: &'static str> Generate<char> for WhileNot1<CHARS> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<char>) {
+        let mut chars: Vec<char> = ('a'..='z')
+            .chain('A'..='Z')
+            .filter(|c| !CHARS.contains(*c))
+            .collect();
+        if chars.is_empty() {
+            chars = (' '..='~').filter(|c| !CHARS.contains(*c)).collect();
+        }
+        for _ in 0..=gen.repeat() {
+            let c = if chars.is_empty() {
+                any_char_except::<CHARS>(gen.rng())
+            } else {
+                *gen.rng().choose(&chars)
+            };
+            output.extend(Some(c));
         }
     }
+}
 
-    fn peek(input: &mut impl Buffer<char>) -> bool {
-        if OneOf::<CHARS>::peek(input) {
-            return false;
+/// Picks a random `char` that isn't in `CHARS`. There are far more `char`s than can be in `CHARS`,
+/// so this soon finds one
+fn any_char_except<const CHARS: &'static str>(rng: &mut Rng) -> char {
+    loop {
+        let c = u32::try_from(rng.below(0x11_0000))
+            .ok()
+            .and_then(char::from_u32);
+        if let Some(c) = c.filter(|&c| !OneOf::<CHARS>::contains(c)) {
+            return c;
         }
-        loop {
-            let mut cursor = input.cursor();
-            if OneOf::<CHARS>::peek(&mut cursor) {
-                break;
+    }
+}
+
+impl<const CHARS: &'static str> Describe<char> for WhileNot1<CHARS> {
+    fn describe(_: &mut Grammar) -> Rule {
+        let chars = Rule::CharSet {
+            chars: CHARS.to_string(),
+            negated: true,
+        };
+        Rule::repeat(chars, 1)
+    }
+}
+
+impl<const CHARS: &'static str> Parse<char> for WhileNot1<CHARS> {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        crate::context::check(input, |input| {
+            let mut output = String::new();
+            if input.read_while(|&c| !OneOf::<CHARS>::contains(c), &mut output) == 0 {
+                Err(eyre::eyre!("no characters found"))
+            } else {
+                Ok(Self(output))
             }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
-        true
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<char>) -> bool {
+        crate::context::check_peek(input, |input| {
+            input.advance_while(|&c| !OneOf::<CHARS>::contains(c)) > 0
+        })
     }
 }
 
@@ -119,6 +177,12 @@ impl<const CHARS: &'static str> From<AnyOf1<CHARS>> for String {
     }
 }
 
+impl<const CHARS: &'static str> From<String> for AnyOf1<CHARS> {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
 impl<const CHARS: &'static str> TryFrom<AnyOf1<CHARS>> for usize {
     type Error = <Self as FromStr>::Err;
     fn try_from(v: AnyOf1<CHARS>) -> Result<Self, Self::Error> {
@@ -126,40 +190,47 @@ impl<const CHARS: &'static str> TryFrom<AnyOf1<CHARS>> for usize {
     }
 }
 
+/// Writes the number in decimal
+impl<const CHARS: &'static str> From<usize> for AnyOf1<CHARS> {
+    fn from(n: usize) -> Self {
+        Self(n.to_string())
+    }
+}
+
+impl<const CHARS: &'static str> Print<char> for AnyOf1<CHARS> {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(self.0.chars());
+    }
+}
+
+impl<const CHARS: &'static str> Generate<char> for AnyOf1<CHARS> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<char>) {
+        Vec1::<OneOf<CHARS>>::generate(gen, output);
+    }
+}
+
+impl<const CHARS: &'static str> Describe<char> for AnyOf1<CHARS> {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        Rule::repeat(OneOf::<CHARS>::describe(grammar), 1)
+    }
+}
+
 impl<const CHARS: &'static str> Parse<char> for AnyOf1<CHARS> {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        let mut output = String::new();
-
-        loop {
-            let mut cursor = input.cursor();
-            match OneOf::<CHARS>::parse(&mut cursor) {
-                Ok(c) => output.push(c.into()),
-                Err(_) => break,
+        crate::context::check(input, |input| {
+            let mut output = String::new();
+            if input.read_while(|&c| OneOf::<CHARS>::contains(c), &mut output) == 0 {
+                Err(eyre::eyre!("no characters found"))
+            } else {
+                Ok(Self(output))
             }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
-
-        if output.is_empty() {
-            Err(eyre::eyre!("no characters found"))
-        } else {
-            Ok(Self(output))
-        }
+        })
     }
 
     fn peek(input: &mut impl Buffer<char>) -> bool {
-        if !OneOf::<CHARS>::peek(input) {
-            return false;
-        }
-        loop {
-            let mut cursor = input.cursor();
-            if !OneOf::<CHARS>::peek(&mut cursor) {
-                break;
-            }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
-        true
+        crate::context::check_peek(input, |input| {
+            input.advance_while(|&c| OneOf::<CHARS>::contains(c)) > 0
+        })
     }
 }
 
@@ -175,4 +246,30 @@ mod tests {
         assert!(AnyOf::<"0123456789">::peek(&mut cursor));
         assert_eq!(cursor.next(), Some('$'));
     }
+
+    /// Every printable ASCII character
+    type NotPrintable = WhileNot1<" !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~">;
+
+    #[test]
+    fn while_not_generates_outside_printable_ascii() {
+        let mut gen = Generator::new(3);
+        for _ in 0..20 {
+            let s: String = crate::generate::<NotPrintable, _, _>(&mut gen);
+            assert!(!s.is_empty());
+            assert!(s.chars().all(|c| !(' '..='~').contains(&c)), "{:?}", s);
+            crate::parse_terminated::<NotPrintable, _>(s.chars()).unwrap();
+        }
+    }
+
+    #[test]
+    fn while_not_peek_agrees_with_parse() {
+        let mut buffer = "".chars().into_buf();
+        assert!(!WhileNot1::<".">::peek(&mut buffer.cursor()));
+        assert!(WhileNot1::<".">::parse(&mut buffer).is_err());
+
+        let mut buffer = "ab.".chars().into_buf();
+        let mut cursor = buffer.cursor();
+        assert!(WhileNot1::<".">::peek(&mut cursor));
+        assert_eq!(cursor.next(), Some('.'));
+    }
 }
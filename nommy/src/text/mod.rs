@@ -6,20 +6,51 @@ mod one_of;
 pub use one_of::*;
 mod many;
 pub use many::*;
+mod set;
 
-use crate::{eyre, Buffer, Parse};
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Generate, Generator, Parse, Print,
+};
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// Parses newline `"\n"` or carriage return `"\r\n"`
 pub struct LineEnding;
 
+/// Prints a newline
+impl Print<char> for LineEnding {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(Some('\n'));
+    }
+}
+
+impl Generate<char> for LineEnding {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<char>) {
+        output.extend(gen.rng().choose(&["\n", "\r\n"]).chars());
+    }
+}
+
+impl Describe<char> for LineEnding {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        grammar.rule("LineEnding", |_| {
+            Rule::choice(vec![
+                Rule::Terminal("\n".to_string()),
+                Rule::Terminal("\r\n".to_string()),
+            ])
+        })
+    }
+}
+
 impl Parse<char> for LineEnding {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        if Self::peek(input) {
-            Ok(Self)
-        } else {
-            Err(eyre::eyre!("could not parse line ending"))
-        }
+        crate::context::check(input, |input| {
+            if Self::peek(input) {
+                Ok(Self)
+            } else {
+                Err(eyre::eyre!("could not parse line ending"))
+            }
+        })
     }
 
     fn peek(input: &mut impl Buffer<char>) -> bool {
@@ -31,17 +62,41 @@ impl Parse<char> for LineEnding {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// Type that parses any space characters (tabs, spaces)
 pub struct Space;
 
+/// Prints a single space
+impl Print<char> for Space {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(Some(' '));
+    }
+}
+
+impl Generate<char> for Space {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<char>) {
+        output.extend(gen.rng().choose(&[" ", "\t"]).chars());
+    }
+}
+
+impl Describe<char> for Space {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        grammar.rule("Space", |_| Rule::CharSet {
+            chars: " \t".to_string(),
+            negated: false,
+        })
+    }
+}
+
 impl Parse<char> for Space {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        if Self::peek(input) {
-            Ok(Self)
-        } else {
-            Err(eyre::eyre!("could not parse space"))
-        }
+        crate::context::check(input, |input| {
+            if Self::peek(input) {
+                Ok(Self)
+            } else {
+                Err(eyre::eyre!("could not parse space"))
+            }
+        })
     }
 
     fn peek(input: &mut impl Buffer<char>) -> bool {
@@ -49,17 +104,44 @@ impl Parse<char> for Space {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// Type that parses any whitespace characters (tabs, spaces, newlines and carriage returns)
 pub struct WhiteSpace;
 
+/// Prints a single space
+impl Print<char> for WhiteSpace {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(Some(' '));
+    }
+}
+
+impl Generate<char> for WhiteSpace {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<char>) {
+        output.extend(gen.rng().choose(&[" ", "\t", "\n", "\r\n"]).chars());
+    }
+}
+
+impl Describe<char> for WhiteSpace {
+    fn describe(grammar: &mut Grammar) -> Rule {
+        grammar.rule("WhiteSpace", |_| {
+            let chars = Rule::CharSet {
+                chars: " \t\n".to_string(),
+                negated: false,
+            };
+            Rule::choice(vec![chars, Rule::Terminal("\r\n".to_string())])
+        })
+    }
+}
+
 impl Parse<char> for WhiteSpace {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        if Self::peek(input) {
-            Ok(Self)
-        } else {
-            Err(eyre::eyre!("could not parse whitespace"))
-        }
+        crate::context::check(input, |input| {
+            if Self::peek(input) {
+                Ok(Self)
+            } else {
+                Err(eyre::eyre!("could not parse whitespace"))
+            }
+        })
     }
 
     fn peek(input: &mut impl Buffer<char>) -> bool {
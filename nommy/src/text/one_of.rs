@@ -1,4 +1,9 @@
-use crate::{eyre, Buffer, Parse};
+use super::set::CharSet;
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Generate, Generator, Parse, Print,
+};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 /// `OneOf` is a generic type that implements [`Parse`] to match one character within the given string
@@ -11,17 +16,63 @@ use crate::{eyre, Buffer, Parse};
 /// ```
 pub struct OneOf<const CHARS: &'static str>(char);
 
+impl<const CHARS: &'static str> OneOf<CHARS> {
+    const SET: CharSet = CharSet::new(CHARS);
+
+    /// Whether `c` is one of `CHARS`. The set of chars is built at compile time,
+    /// so this doesn't have to search through `CHARS`
+    ///
+    /// ```
+    /// use nommy::text::OneOf;
+    /// assert!(OneOf::<"abc">::contains('b'));
+    /// assert!(!OneOf::<"abc">::contains('d'));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn contains(c: char) -> bool {
+        Self::SET.contains(c)
+    }
+}
+
 impl<const CHARS: &'static str> From<OneOf<CHARS>> for char {
     fn from(v: OneOf<CHARS>) -> Self {
         v.0
     }
 }
 
+impl<const CHARS: &'static str> From<char> for OneOf<CHARS> {
+    fn from(c: char) -> Self {
+        Self(c)
+    }
+}
+
+impl<const CHARS: &'static str> Print<char> for OneOf<CHARS> {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(Some(self.0));
+    }
+}
+
+impl<const CHARS: &'static str> Generate<char> for OneOf<CHARS> {
+    fn generate(gen: &mut Generator, output: &mut impl Extend<char>) {
+        let chars: Vec<char> = CHARS.chars().collect();
+        output.extend(Some(*gen.rng().choose(&chars)));
+    }
+}
+
+impl<const CHARS: &'static str> Describe<char> for OneOf<CHARS> {
+    fn describe(_: &mut Grammar) -> Rule {
+        Rule::CharSet {
+            chars: CHARS.to_string(),
+            negated: false,
+        }
+    }
+}
+
 impl<const CHARS: &'static str> Parse<char> for OneOf<CHARS> {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        match input.next() {
+        crate::context::check(input, |input| match input.next() {
             Some(c) => {
-                if CHARS.contains(c) {
+                if Self::contains(c) {
                     Ok(Self(c))
                 } else {
                     Err(eyre::eyre!(
@@ -32,12 +83,12 @@ impl<const CHARS: &'static str> Parse<char> for OneOf<CHARS> {
                 }
             }
             None => Err(eyre::eyre!("error parsing one of {:?}, reached EOF", CHARS)),
-        }
+        })
     }
 
     fn peek(input: &mut impl Buffer<char>) -> bool {
         match input.next() {
-            Some(c) => CHARS.contains(c),
+            Some(c) => Self::contains(c),
             None => false,
         }
     }
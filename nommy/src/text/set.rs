@@ -0,0 +1,167 @@
+use std::cmp::Ordering;
+
+/// The most non-ASCII ranges a [`CharSet`] stores.
+/// Sets with more ranges than this search the string instead
+const MAX_RANGES: usize = 32;
+
+/// A set of chars, built at compile time from a string of the chars in the set.
+/// ASCII chars are looked up in a bitmap, and any other chars are binary searched in a sorted list of ranges
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct CharSet {
+    chars: &'static str,
+    ascii: u128,
+    ranges: [(u32, u32); MAX_RANGES],
+    len: usize,
+    /// Whether there were too many ranges to store
+    overflow: bool,
+}
+
+impl CharSet {
+    pub(crate) const fn new(chars: &'static str) -> Self {
+        let bytes = chars.as_bytes();
+        let mut set = Self {
+            chars,
+            ascii: 0,
+            ranges: [(0, 0); MAX_RANGES],
+            len: 0,
+            overflow: false,
+        };
+
+        let mut i = 0;
+        while i < bytes.len() {
+            let (c, width) = decode_utf8(bytes, i);
+            i += width;
+            if c < 128 {
+                set.ascii |= 1 << c;
+                continue;
+            }
+            if set.overflow {
+                continue;
+            }
+
+            // the first range that `c` is in, directly follows, or comes before
+            let mut p = 0;
+            while p < set.len && set.ranges[p].1 + 1 < c {
+                p += 1;
+            }
+
+            if p < set.len && set.ranges[p].0 <= c {
+                if set.ranges[p].1 < c {
+                    // `c` directly follows the range, which might now meet the next one
+                    set.ranges[p].1 = c;
+                    if p + 1 < set.len && set.ranges[p + 1].0 == c + 1 {
+                        set.ranges[p].1 = set.ranges[p + 1].1;
+                        let mut j = p + 1;
+                        while j + 1 < set.len {
+                            set.ranges[j] = set.ranges[j + 1];
+                            j += 1;
+                        }
+                        set.len -= 1;
+                    }
+                }
+            } else if p < set.len && set.ranges[p].0 == c + 1 {
+                set.ranges[p].0 = c;
+            } else if set.len == MAX_RANGES {
+                set.overflow = true;
+            } else {
+                let mut j = set.len;
+                while j > p {
+                    set.ranges[j] = set.ranges[j - 1];
+                    j -= 1;
+                }
+                set.ranges[p] = (c, c);
+                set.len += 1;
+            }
+        }
+
+        set
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, c: char) -> bool {
+        if c.is_ascii() {
+            self.ascii & (1 << c as u32) != 0
+        } else if self.overflow {
+            self.chars.contains(c)
+        } else {
+            let c = c as u32;
+            self.ranges[..self.len]
+                .binary_search_by(|&(start, end)| {
+                    if end < c {
+                        Ordering::Less
+                    } else if start > c {
+                        Ordering::Greater
+                    } else {
+                        Ordering::Equal
+                    }
+                })
+                .is_ok()
+        }
+    }
+}
+
+/// Decodes the char starting at `bytes[i]`, returning it along with how many bytes it took up.
+/// `bytes` must be valid UTF-8
+const fn decode_utf8(bytes: &[u8], i: usize) -> (u32, usize) {
+    let b = bytes[i] as u32;
+    if b < 0x80 {
+        (b, 1)
+    } else if b < 0xe0 {
+        (((b & 0x1f) << 6) | cont(bytes, i + 1), 2)
+    } else if b < 0xf0 {
+        (
+            ((b & 0x0f) << 12) | (cont(bytes, i + 1) << 6) | cont(bytes, i + 2),
+            3,
+        )
+    } else {
+        (
+            ((b & 0x07) << 18)
+                | (cont(bytes, i + 1) << 12)
+                | (cont(bytes, i + 2) << 6)
+                | cont(bytes, i + 3),
+            4,
+        )
+    }
+}
+
+/// The bits of a UTF-8 continuation byte
+const fn cont(bytes: &[u8], i: usize) -> u32 {
+    (bytes[i] & 0x3f) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(chars: &'static str) {
+        let set = CharSet::new(chars);
+        for c in (0..0x3000).filter_map(std::char::from_u32) {
+            assert_eq!(set.contains(c), chars.contains(c), "{:?} in {:?}", c, chars);
+        }
+    }
+
+    #[test]
+    fn ascii() {
+        check("");
+        check("abc_0123456789\u{0}\u{7f}");
+    }
+
+    #[test]
+    fn ranges() {
+        check("λμνξ→←é");
+        check("βαγ");
+        check("ace\u{e2}\u{e4}\u{e3}\u{e1}");
+
+        let set = CharSet::new("βγαδε");
+        assert_eq!(set.len, 1);
+        assert_eq!(set.ranges[0], ('α' as u32, 'ε' as u32));
+    }
+
+    #[test]
+    fn too_many_ranges() {
+        let chars = "ÀÂÄÆÈÊÌÎÐÒÔÖØÚÜÞàâäæèêìîðòôöøúüþĀĂĄĆĈ";
+        let set = CharSet::new(chars);
+        assert!(set.overflow);
+        check(chars);
+    }
+}
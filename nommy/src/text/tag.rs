@@ -1,8 +1,12 @@
-use std::iter::FromIterator;
+use std::{error::Error, fmt};
 
-use crate::{eyre, Buffer, Parse};
+use crate::{
+    eyre,
+    grammar::{Grammar, Rule},
+    Buffer, Describe, Generate, Generator, Parse, Print,
+};
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// `Tag` is a generic type that implements [`Parse`] to match the given string exactly
 ///
 /// ```
@@ -13,18 +17,79 @@ use crate::{eyre, Buffer, Parse};
 /// ```
 pub struct Tag<const TAG: &'static str>;
 
+impl<const TAG: &'static str> Print<char> for Tag<TAG> {
+    fn print(&self, output: &mut impl Extend<char>) {
+        output.extend(TAG.chars());
+    }
+}
+
+impl<const TAG: &'static str> Generate<char> for Tag<TAG> {
+    fn generate(_: &mut Generator, output: &mut impl Extend<char>) {
+        output.extend(TAG.chars());
+    }
+}
+
+impl<const TAG: &'static str> Describe<char> for Tag<TAG> {
+    fn describe(_: &mut Grammar) -> Rule {
+        Rule::Terminal(TAG.to_string())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Error returned when a [`Tag`] doesn't match.
+/// The text that was found instead is only built when the error is displayed
+///
+/// ```
+/// use nommy::{parse, text::{Tag, TagError}};
+/// let res: Result<Tag<"null">, _> = parse("nil".chars());
+/// let err = res.unwrap_err();
+/// assert_eq!(err.downcast_ref(), Some(&TagError { tag: "null", matched: 1, found: Some('i') }));
+/// assert_eq!(err.to_string(), "failed to parse tag \"null\", found \"ni\"");
+/// ```
+pub struct TagError {
+    /// The tag that was expected
+    pub tag: &'static str,
+    /// How many chars of the tag matched
+    pub matched: usize,
+    /// The char that didn't match, or `None` if the input ended
+    pub found: Option<char>,
+}
+
+impl Error for TagError {}
+impl fmt::Display for TagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let found: String = self
+            .tag
+            .chars()
+            .take(self.matched)
+            .chain(self.found)
+            .collect();
+        write!(f, "failed to parse tag {:?}, found {:?}", self.tag, found)
+    }
+}
+
 impl<const TAG: &'static str> Parse<char> for Tag<TAG> {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        let s = String::from_iter(input.take(TAG.len()));
-        if TAG == s {
+        crate::context::check(input, |input| {
+            for (matched, c) in TAG.chars().enumerate() {
+                match input.next() {
+                    Some(found) if found == c => {}
+                    found => {
+                        return Err(TagError {
+                            tag: TAG,
+                            matched,
+                            found,
+                        }
+                        .into())
+                    }
+                }
+            }
             Ok(Self)
-        } else {
-            Err(eyre::eyre!("failed to parse tag {:?}, found {:?}", TAG, s))
-        }
+        })
     }
 
     fn peek(input: &mut impl Buffer<char>) -> bool {
-        TAG.chars().eq(input.take(TAG.len()))
+        TAG.chars().all(|c| input.next() == Some(c))
     }
 }
 
@@ -76,4 +141,19 @@ mod tests {
             "failed to parse tag \")\", found \"1\""
         );
     }
+
+    #[test]
+    fn test_non_ascii() {
+        let mut input = "λx.x→y".chars().into_buf();
+        Tag::<"λx">::parse(&mut input).unwrap();
+        assert!(Tag::<".x→">::peek(&mut input.cursor()));
+        Tag::<".x→">::parse(&mut input).unwrap();
+        assert_eq!(input.next(), Some('y'));
+
+        let res: Result<Tag<"→→">, _> = parse("→".chars());
+        assert_eq!(
+            format!("{}", res.unwrap_err()),
+            "failed to parse tag \"→→\", found \"→\""
+        );
+    }
 }
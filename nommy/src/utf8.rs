@@ -0,0 +1,375 @@
+use std::{collections::VecDeque, error::Error, fmt};
+
+use crate::{context::Context, eyre, memo::Memo, Buffer, Cursor, Parse};
+
+/// `Utf8Buf` is a [`Buffer`] of [`char`]s, decoding UTF-8 from any [`Buffer<u8>`] as it is read.
+///
+/// The underlying buffer is only consumed once the chars are, so [`Utf8Buf::into_inner`] can be used
+/// to go back to parsing bytes. Decoding stops at the first invalid sequence, so that the bytes from there
+/// can still be read that way, and the chars end there. The reason is available from [`Utf8Buf::error`],
+/// and [`Utf8Buf::parse`] and [`Utf8Buf::parse_terminated`] fail with it if the parse ran into it
+///
+/// ```
+/// use nommy::{Buffer, IntoBuf, Parse, Utf8Buf, Utf8Error, text::AnyOf1};
+/// let mut buffer = Utf8Buf::new(b"caf\xc3\xa9 \xff".to_vec().into_buf());
+/// let word: String = AnyOf1::<"abcdefé">::parse(&mut buffer).unwrap().into();
+/// assert_eq!(word, "café");
+/// assert_eq!(buffer.byte_offset(), 5);
+///
+/// assert_eq!(buffer.next(), Some(' '));
+/// assert_eq!(buffer.next(), None);
+/// assert_eq!(buffer.error(), Some(&Utf8Error::Invalid { offset: 6 }));
+///
+/// let mut buffer = Utf8Buf::new(b"caf\xc3\xa9\xff".to_vec().into_buf());
+/// let err = buffer.parse_terminated::<AnyOf1<"abcdefé">>().unwrap_err();
+/// assert_eq!(err.downcast_ref(), Some(&Utf8Error::Invalid { offset: 5 }));
+/// ```
+pub struct Utf8Buf<B: Buffer<u8>> {
+    inner: B,
+    /// Decoded chars that have not been consumed yet, along with their encoded length
+    chars: VecDeque<(char, usize)>,
+    /// How many bytes of `inner` are covered by `chars`
+    decoded: usize,
+    /// How many bytes of `inner` have been consumed
+    offset: usize,
+    /// How many chars have been consumed, which is what memoized results are keyed by
+    consumed: usize,
+    error: Option<Utf8Error>,
+    memo: Memo,
+    context: Context,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Error produced when [`Utf8Buf`] finds bytes that are not valid UTF-8
+pub enum Utf8Error {
+    /// The sequence starting at `offset` is not valid UTF-8
+    Invalid {
+        /// Byte offset of the start of the sequence
+        offset: usize,
+    },
+    /// The input ended in the middle of the sequence starting at `offset`
+    Incomplete {
+        /// Byte offset of the start of the sequence
+        offset: usize,
+    },
+}
+
+impl Utf8Error {
+    /// Byte offset of the start of the invalid sequence
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        match *self {
+            Self::Invalid { offset } | Self::Incomplete { offset } => offset,
+        }
+    }
+}
+
+impl Error for Utf8Error {}
+impl fmt::Display for Utf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid { offset } => {
+                write!(f, "invalid UTF-8 sequence at byte offset {}", offset)
+            }
+            Self::Incomplete { offset } => {
+                write!(f, "incomplete UTF-8 sequence at byte offset {}", offset)
+            }
+        }
+    }
+}
+
+impl<B: Buffer<u8>> Utf8Buf<B> {
+    /// Create a new `Utf8Buf` reading from `inner`
+    #[must_use]
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            chars: VecDeque::new(),
+            decoded: 0,
+            offset: 0,
+            consumed: 0,
+            error: None,
+            memo: Memo::new(),
+            context: Context::new(),
+        }
+    }
+
+    /// Replaces the default [`Context`], to change the limits of the parse
+    #[must_use]
+    pub fn with_context(mut self, context: Context) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Returns how many bytes of the underlying buffer have been consumed
+    #[must_use]
+    pub fn byte_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the invalid sequence that decoding stopped at, if any
+    #[must_use]
+    pub fn error(&self) -> Option<&Utf8Error> {
+        self.error.as_ref()
+    }
+
+    /// Parses `P` from the chars, like [`Parse::parse`], but fails with the [`Utf8Error`]
+    /// if `P` failed or stopped at the invalid sequence, since then it might have only seen part of the input
+    ///
+    /// # Errors
+    /// If `P` failed to parse, or the input was not valid UTF-8 up to where it stopped
+    pub fn parse<P: Parse<char>>(&mut self) -> eyre::Result<P> {
+        let result = P::parse(self);
+        match self.error {
+            Some(error) if result.is_err() || error.offset() == self.offset => Err(error.into()),
+            _ => result,
+        }
+    }
+
+    /// Parses `P` from the chars, like [`parse_terminated`](crate::parse_terminated), erroring if the full input
+    /// was not consumed. Unlike [`Utf8Buf::parse`], this fails with the [`Utf8Error`] wherever it is
+    ///
+    /// # Errors
+    /// If `P` failed to parse, the input was not valid UTF-8, or the input was not parsed completely
+    pub fn parse_terminated<P: Parse<char>>(&mut self) -> eyre::Result<P> {
+        let output = self.parse()?;
+        if self.next().is_some() {
+            Err(eyre::eyre!("input was not parsed completely"))
+        } else if let Some(error) = self.error {
+            Err(error.into())
+        } else {
+            Ok(output)
+        }
+    }
+
+    /// Returns the underlying buffer, positioned after the last consumed char
+    #[must_use]
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// Decodes the next char onto the end of `chars`. Returns false at the end of input or on invalid input
+    fn decode_next(&mut self) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        let first = match self.inner.peek_ahead(self.decoded) {
+            Some(b) => b,
+            None => return false,
+        };
+        let offset = self.offset + self.decoded;
+        let len = match first {
+            0x00..=0x7f => 1,
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => {
+                self.error = Some(Utf8Error::Invalid { offset });
+                return false;
+            }
+        };
+
+        let mut bytes = [first, 0, 0, 0];
+        for (i, byte) in bytes[..len].iter_mut().enumerate().skip(1) {
+            if let Some(b) = self.inner.peek_ahead(self.decoded + i) {
+                *byte = b;
+            } else {
+                self.error = Some(Utf8Error::Incomplete { offset });
+                return false;
+            }
+        }
+
+        if let Ok(s) = std::str::from_utf8(&bytes[..len]) {
+            let c = s.chars().next().expect("decoded at least one byte");
+            self.chars.push_back((c, len));
+            self.decoded += len;
+            true
+        } else {
+            self.error = Some(Utf8Error::Invalid { offset });
+            false
+        }
+    }
+
+    /// Reads the next char without counting it as a step of the [`Context`]
+    fn consume(&mut self) -> Option<char> {
+        if self.chars.is_empty() && !self.decode_next() {
+            return None;
+        }
+        let (c, len) = self.chars.pop_front()?;
+        self.inner.fast_forward(len);
+        self.decoded -= len;
+        self.offset += len;
+        self.consumed += 1;
+        Some(c)
+    }
+}
+
+impl<B: Buffer<u8>> Iterator for Utf8Buf<B> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if !self.context.step() {
+            return None;
+        }
+        self.consume()
+    }
+}
+
+impl<B: Buffer<u8>> Buffer<char> for Utf8Buf<B> {
+    type CursorBase = Self;
+    fn cursor(&mut self) -> Cursor<char, Self::CursorBase> {
+        Cursor::new(self)
+    }
+
+    fn fast_forward(&mut self, n: usize) {
+        for _ in 0..n {
+            if self.consume().is_none() {
+                break;
+            }
+        }
+        self.memo.forget_before(self.consumed);
+    }
+
+    fn peek_ahead(&mut self, i: usize) -> Option<char> {
+        if !self.context.step() {
+            return None;
+        }
+        while self.chars.len() <= i {
+            if !self.decode_next() {
+                return None;
+            }
+        }
+        Some(self.chars[i].0)
+    }
+
+    /// Memoized results are keyed by chars consumed, like the positions of cursors
+    fn memo(&mut self) -> Option<(usize, &mut Memo)> {
+        Some((self.consumed, &mut self.memo))
+    }
+
+    fn context(&mut self) -> Option<&mut Context> {
+        Some(&mut self.context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eyre, memo,
+        text::{AnyOf1, OneOf, Tag},
+        IntoBuf, Parse,
+    };
+
+    #[test]
+    fn decode() {
+        let input = "aé€😀".as_bytes().to_vec();
+        let mut buffer = Utf8Buf::new(input.into_buf());
+        assert_eq!(buffer.next(), Some('a'));
+        assert_eq!(buffer.byte_offset(), 1);
+        assert_eq!(buffer.next(), Some('é'));
+        assert_eq!(buffer.byte_offset(), 3);
+        assert_eq!(buffer.next(), Some('€'));
+        assert_eq!(buffer.byte_offset(), 6);
+        assert_eq!(buffer.next(), Some('😀'));
+        assert_eq!(buffer.byte_offset(), 10);
+        assert_eq!(buffer.next(), None);
+        assert_eq!(buffer.error(), None);
+    }
+
+    #[test]
+    fn cursor() {
+        let mut buffer = Utf8Buf::new("€uro".as_bytes().to_vec().into_buf());
+        let mut cursor = buffer.cursor();
+        assert!(OneOf::<"$€">::peek(&mut cursor));
+        assert!(Tag::<"u">::peek(&mut cursor));
+        assert_eq!(buffer.byte_offset(), 0);
+
+        OneOf::<"$€">::parse(&mut buffer).unwrap();
+        Tag::<"u">::parse(&mut buffer).unwrap();
+        assert_eq!(buffer.byte_offset(), 4);
+
+        let mut inner = buffer.into_inner();
+        assert_eq!(inner.next(), Some(b'r'));
+    }
+
+    #[test]
+    fn invalid() {
+        // surrogates are not valid UTF-8
+        let mut buffer = Utf8Buf::new(b"ab\xed\xa0\x80".to_vec().into_buf());
+        assert_eq!(buffer.peek_ahead(2), None);
+        assert_eq!(buffer.error(), Some(&Utf8Error::Invalid { offset: 2 }));
+        assert_eq!(buffer.collect::<String>(), "ab");
+    }
+
+    #[test]
+    fn incomplete() {
+        let mut buffer = Utf8Buf::new(b"a\xe2\x82".to_vec().into_buf());
+        assert_eq!(buffer.next(), Some('a'));
+        assert_eq!(buffer.next(), None);
+        let err = buffer.error().unwrap();
+        assert_eq!(err.offset(), 1);
+        assert_eq!(
+            err.to_string(),
+            "incomplete UTF-8 sequence at byte offset 1"
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        // a valid prefix doesn't parse as the whole input
+        let mut buffer = Utf8Buf::new(b"abc\xff".to_vec().into_buf());
+        let err = buffer.parse_terminated::<AnyOf1<"abc">>().unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&Utf8Error::Invalid { offset: 3 }));
+
+        let mut buffer = Utf8Buf::new(b"abc\xe2\x82".to_vec().into_buf());
+        let err = buffer.parse::<AnyOf1<"abc">>().unwrap_err();
+        assert_eq!(
+            err.downcast_ref(),
+            Some(&Utf8Error::Incomplete { offset: 3 })
+        );
+
+        let mut buffer = Utf8Buf::new(b"\xffabc".to_vec().into_buf());
+        let err = buffer.parse::<AnyOf1<"abc">>().unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&Utf8Error::Invalid { offset: 0 }));
+
+        // stopping before the invalid sequence is fine, as long as it's not meant to be the end
+        let mut buffer = Utf8Buf::new(b"ab c\xff".to_vec().into_buf());
+        let word: String = buffer.parse::<AnyOf1<"abc">>().unwrap().into();
+        assert_eq!(word, "ab");
+        assert!(buffer.parse_terminated::<Tag<" c">>().is_err());
+
+        let mut buffer = Utf8Buf::new(b"ab c".to_vec().into_buf());
+        buffer.parse::<AnyOf1<"abc">>().unwrap();
+        buffer.parse_terminated::<Tag<" c">>().unwrap();
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Word(String);
+
+    impl Parse<char> for Word {
+        fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+            memo::parse(input, |input| {
+                Ok(Self(AnyOf1::<"abcdeé">::parse(input)?.into()))
+            })
+        }
+
+        fn peek(input: &mut impl Buffer<char>) -> bool {
+            memo::peek::<Self, _, _>(input, |input| AnyOf1::<"abcdeé">::peek(input))
+        }
+    }
+
+    #[test]
+    fn memo_multi_byte() {
+        let mut buffer = Utf8Buf::new("é b".as_bytes().to_vec().into_buf());
+        let mut cursor = buffer.cursor();
+        cursor.fast_forward(2);
+        assert_eq!(Word::parse(&mut cursor).unwrap(), Word("b".to_string()));
+
+        // 'é' is two bytes long, but the space after it is at a different position to the 'b'
+        assert_eq!(buffer.next(), Some('é'));
+        assert!(Word::parse(&mut buffer).is_err());
+        assert_eq!(buffer.next(), Some(' '));
+        assert_eq!(Word::parse(&mut buffer).unwrap(), Word("b".to_string()));
+    }
+}
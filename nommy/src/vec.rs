@@ -178,6 +178,166 @@ where
     Ok(output)
 }
 
+/// Peeks the same input that [`parse_vec`] parses, returning how many values were found
+pub fn peek_vec<P, Ignore, T, B>(max: usize, input: &mut B) -> usize
+where
+    Ignore: Parse<T>,
+    P: Parse<T>,
+    B: Buffer<T>,
+{
+    if max == 0 {
+        return 0;
+    }
+
+    let mut cursor = input.cursor();
+    if !P::peek(&mut cursor) {
+        return 0;
+    }
+    let pos = cursor.position();
+    input.fast_forward(pos);
+
+    let mut count = 1;
+    loop {
+        if max == count {
+            break;
+        }
+
+        let mut cursor = input.cursor();
+        Vec::<Ignore>::peek(&mut cursor);
+
+        if !P::peek(&mut cursor) {
+            break;
+        }
+
+        let pos = cursor.position();
+        input.fast_forward(pos);
+        count += 1;
+    }
+
+    count
+}
+
+/// Peeks the same input that [`parse_vec_seperated_by`] parses, returning how many values were found
+pub fn peek_vec_seperated_by<P, SeperatedBy, Ignore, T, B>(max: usize, input: &mut B) -> usize
+where
+    SeperatedBy: Parse<T>,
+    Ignore: Parse<T>,
+    P: Parse<T>,
+    B: Buffer<T>,
+{
+    if max == 0 {
+        return 0;
+    }
+
+    let mut cursor = input.cursor();
+    if !P::peek(&mut cursor) {
+        return 0;
+    }
+    let pos = cursor.position();
+    input.fast_forward(pos);
+
+    let mut count = 1;
+    loop {
+        if max == count {
+            break;
+        }
+
+        let mut cursor = input.cursor();
+
+        Vec::<Ignore>::peek(&mut cursor);
+        if !SeperatedBy::peek(&mut cursor) {
+            break;
+        }
+        Vec::<Ignore>::peek(&mut cursor);
+
+        if !P::peek(&mut cursor) {
+            break;
+        }
+
+        let pos = cursor.position();
+        input.fast_forward(pos);
+        count += 1;
+    }
+
+    count
+}
+
+/// Peeks the same input that [`parse_vec_seperated_by_trailing`] parses, returning how many values were found
+pub fn peek_vec_seperated_by_trailing<P, SeperatedBy, Ignore, T, B>(
+    max: usize,
+    input: &mut B,
+) -> usize
+where
+    SeperatedBy: Parse<T>,
+    Ignore: Parse<T>,
+    P: Parse<T>,
+    B: Buffer<T>,
+{
+    let mut count = 0;
+    loop {
+        if max == count {
+            break;
+        }
+
+        let mut cursor = input.cursor();
+        if !P::peek(&mut cursor) {
+            break;
+        }
+
+        Vec::<Ignore>::peek(&mut cursor);
+        if !SeperatedBy::peek(&mut cursor) {
+            break;
+        }
+        Vec::<Ignore>::peek(&mut cursor);
+        let pos = cursor.position();
+        input.fast_forward(pos);
+
+        count += 1;
+    }
+
+    count
+}
+
+/// Peeks the same input that [`parse_vec_seperated_by_maybe_trailing`] parses, returning how many values were found
+pub fn peek_vec_seperated_by_maybe_trailing<P, SeperatedBy, Ignore, T, B>(
+    max: usize,
+    input: &mut B,
+) -> usize
+where
+    SeperatedBy: Parse<T>,
+    Ignore: Parse<T>,
+    P: Parse<T>,
+    B: Buffer<T>,
+{
+    let mut count = 0;
+    loop {
+        if max == count {
+            break;
+        }
+
+        let mut cursor = input.cursor();
+        if !P::peek(&mut cursor) {
+            break;
+        }
+        let pos = cursor.position();
+        input.fast_forward(pos);
+        count += 1;
+
+        Vec::<Ignore>::peek(input);
+
+        let mut cursor = input.cursor();
+        if !SeperatedBy::peek(&mut cursor) {
+            break;
+        }
+        let pos = cursor.position();
+        input.fast_forward(pos);
+
+        Vec::<Ignore>::peek(input);
+    }
+
+    count
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -187,7 +347,7 @@ mod tests {
 
     use super::{
         parse_vec, parse_vec_seperated_by, parse_vec_seperated_by_maybe_trailing,
-        parse_vec_seperated_by_trailing,
+        parse_vec_seperated_by_trailing, peek_vec_seperated_by,
     };
 
     #[test]
@@ -303,4 +463,18 @@ mod tests {
         assert_eq!(res, vec![123, 321, 0]);
         assert_eq!(input.collect::<String>(), "...".to_string())
     }
+
+    #[test]
+    fn sequence_peek() {
+        let mut input = "foo, bar , baz,...".chars().into_buf();
+        let count = peek_vec_seperated_by::<
+            AnyOf1<"abcdefghijklmnopqrstuvwxyz">,
+            Tag<",">,
+            WhiteSpace,
+            _,
+            _,
+        >(usize::MAX, &mut input);
+        assert_eq!(count, 3);
+        assert_eq!(input.collect::<String>(), ",...".to_string())
+    }
 }